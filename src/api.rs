@@ -1,291 +1,548 @@
-use std::process;
+use std::{
+    collections::HashSet,
+    io::IsTerminal,
+    thread,
+    time::{Duration, Instant},
+};
 
-use isahc::{get, post, prelude::*, Request};
-use serde_json::{Result as SerdeResult, Value};
+use serde::Serialize;
+use stewardx_cli::{
+    CmdTaskProps, DockerImageType, Error, NewTask, PreparedRequest, Report, Result, StewardxClient,
+    Task, TaskProps, TaskUpdate,
+};
 
-use crate::output::{
-    pretty_print_reports, pretty_print_tasks, print_stewardx_connection_failure, print_json_failure,
+use crate::{
+    filter::{ReportFilter, TaskFilter},
+    output::{
+        print_bulk_results, print_changed_task, print_report, print_report_details, print_report_output,
+        print_reports, print_task, print_tasks, print_watched_tasks, OutputOptions, ReportStream, TaskChange,
+    },
+    utils::{docker_image, format_date, format_duration, parse_frequency},
 };
 
-fn get_stewardx_url() -> String {
-    match std::env::var("STEWARDX_URL") {
-        Ok(url) => url,
-        Err(_) => {
-            let host = std::env::var("STEWARDX_HOST").unwrap_or("localhost".into());
-            let port = std::env::var("STEWARDX_PORT").unwrap_or("3000".into());
-            format!("http://{}:{}", host, port)
+pub fn get_active_tasks(client: &StewardxClient, options: &OutputOptions) -> Result<()> {
+    let tasks = client.active_tasks()?;
+    print_tasks(&tasks, options)
+}
+
+/// Redraws the active tasks every interval until interrupted. Tasks that started since the
+/// previous refresh are marked as such, ones that finished are shown once more.
+pub fn watch_active_tasks(client: &StewardxClient, interval: Duration) -> Result<()> {
+    let is_terminal = std::io::stdout().is_terminal();
+    let every = chrono::Duration::from_std(interval)
+        .map(format_duration)
+        .unwrap_or_default();
+    let mut previous: Option<Vec<Task>> = None;
+    loop {
+        let tasks = client.active_tasks()?;
+        let now = chrono::Utc::now().naive_utc();
+        let mut rows = tasks
+            .iter()
+            .map(|task| {
+                let change = match &previous {
+                    Some(previous) if !previous.iter().any(|p| p.id == task.id) => TaskChange::Started,
+                    _ => TaskChange::Unchanged,
+                };
+                (task.clone(), change)
+            })
+            .collect::<Vec<_>>();
+        if let Some(previous) = &previous {
+            let finished = previous.iter().filter(|p| !tasks.iter().any(|t| t.id == p.id));
+            rows.extend(finished.map(|task| (task.clone(), TaskChange::Finished)));
+        }
+        if is_terminal {
+            // Clear the screen and move the cursor to the top left, like watch(1) does
+            out!("\x1b[2J\x1b[H");
+        } else if previous.is_some() {
+            outln!();
         }
+        outln!("Every {}: {} active tasks at {}", every, tasks.len(), format_date(now));
+        outln!();
+        print_watched_tasks(&rows, now);
+        previous = Some(tasks);
+        thread::sleep(interval);
     }
 }
 
-pub fn get_active_tasks() {
-    let url = format!("{}/activetasks", get_stewardx_url());
-    let tasks: Result<SerdeResult<Value>, isahc::Error> = get(url).map(|mut t| t.json());
-    let tasks = match tasks {
-        Ok(a) => match a {
-            Ok(val) => val,
-            Err(e) => {
-                print_json_failure(e);
-                process::exit(1);
-            }
-        },
-        Err(e) => {
-            print_stewardx_connection_failure(e);
-            process::exit(1);
-        }
-    };
-    let tasks = tasks.as_array().map(|v| v.to_owned()).unwrap_or(Vec::new());
-    pretty_print_tasks(tasks.to_vec());
+pub fn get_tasks(client: &StewardxClient, filter: &TaskFilter, options: &OutputOptions) -> Result<()> {
+    let tasks = filter.apply(client.tasks()?);
+    print_tasks(&tasks, options)
 }
 
-pub fn get_tasks() {
-    let url = format!("{}/tasks", get_stewardx_url());
-    let tasks: Result<SerdeResult<Value>, isahc::Error> = get(url).map(|mut t| t.json());
-    let tasks = match tasks {
-        Ok(a) => match a {
-            Ok(val) => val,
-            Err(e) => {
-                print_json_failure(e);
-                process::exit(1);
-            }
-        },
-        Err(e) => {
-            print_stewardx_connection_failure(e);
-            process::exit(1);
-        }
-    };
-    let tasks = tasks.as_array().map(|v| v.to_owned()).unwrap_or(Vec::new());
-    pretty_print_tasks(tasks.to_vec());
+pub fn get_task(client: &StewardxClient, id: &str, options: &OutputOptions) -> Result<()> {
+    let task = client.task(id)?;
+    print_task(&task, options)
 }
 
-pub fn get_task(id: &str) {
-    let url = format!("{}/tasks/{}", get_stewardx_url(), id);
-    let tasks: Result<SerdeResult<Value>, isahc::Error> = get(url).map(|mut t| t.json());
-    let task = match tasks {
-        Ok(a) => match a {
-            Ok(val) => val,
-            Err(e) => {
-                print_json_failure(e);
-                process::exit(1);
-            }
-        },
-        Err(e) => {
-            print_stewardx_connection_failure(e);
-            process::exit(1);
-        }
-    };
-    match serde_json::to_string_pretty(&task) {
-        Ok(o) => {
-            println!("{}", o);
-        }
-        Err(e) => {
-            print_json_failure(e);
-            process::exit(1);
-        }
-    };
+/// How a destructive command is allowed to go ahead
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Confirmation {
+    /// Don't ask, the user already agreed with --yes
+    pub yes: bool,
+    /// Only print the requests that would be sent
+    pub dry_run: bool,
 }
 
-pub fn delete_task(id: &str) {
-    let url = format!("{}/tasks", get_stewardx_url());
-    let request = Request::builder()
-        .uri(url)
-        .method(isahc::http::Method::DELETE)
-        .body(serde_json::json!({ "task_id": id }).to_string())
-        .unwrap();
-    let response = request.send();
-    match response {
-        Ok(mut r) => {
-            let result: SerdeResult<Value> = r.json();
-            match result {
-                Ok(r) => {
-                    let status = &r["status"];
-                    if let Some(status) = status.as_str() {
-                        println!("Task deletion status: {}", status);
-                    } else {
-                        println!("Task deletion is failed, please check StewardX logs.");
-                    }
-                }
-                Err(e) => {
-                    print_json_failure(e);
-                    process::exit(1);
-                }
-            };
-        }
-        Err(e) => {
-            print_stewardx_connection_failure(e);
-            process::exit(1);
+/// Lists the tasks about to be changed and asks the user to go ahead. Nobody could answer
+/// without a terminal, so --yes is required then.
+pub fn confirm(operation: TaskOperation, selected: &[(String, Option<Task>)]) -> Result<bool> {
+    if !std::io::stdin().is_terminal() {
+        return Err(Error::Usage(format!(
+            "refusing to {} without confirmation: stdin isn't a terminal, pass --yes",
+            operation.verb()
+        )));
+    }
+    for (id, task) in selected {
+        match task {
+            Some(task) => eprintln!("  {} ({}, {}) {}", task.task_name, task.task_type, task.frequency, id),
+            None => eprintln!("  unknown task {}", id),
         }
-    };
+    }
+    let noun = if selected.len() == 1 { "task" } else { "tasks" };
+    eprint!("Going to {} {} {}, continue? [y/N] ", operation.verb(), selected.len(), noun);
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer).unwrap_or_default();
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
 }
 
-pub fn execute_task(id: &str) {
-    let url = format!("{}/execute/{}", get_stewardx_url(), id);
-    let task: Result<SerdeResult<Value>, isahc::Error> = post(url, ()).map(|mut t| t.json());
-    match task {
-        Ok(result) => {
-            match result {
-                Ok(r) => {
-                    let status = &r["status"];
-                    if let Some(status) = status.as_str() {
-                        println!("Task execution status: {}", status);
-                    } else {
-                        println!("Task execution is failed, please check StewardX logs.");
-                    }
+/// Prints the request in a dry run, otherwise asks for confirmation when it's needed.
+/// Returns whether the operation should go ahead.
+fn confirm_single(
+    client: &StewardxClient,
+    operation: TaskOperation,
+    id: &str,
+    confirmation: &Confirmation,
+) -> Result<bool> {
+    if confirmation.dry_run {
+        outln!("{}", operation.request(client, id));
+        return Ok(false);
+    }
+    if confirmation.yes {
+        return Ok(true);
+    }
+    let task = client.task(id)?;
+    if confirm(operation, &[(task.id.clone(), Some(task))])? {
+        Ok(true)
+    } else {
+        eprintln!("Cancelled, nothing was sent");
+        Ok(false)
+    }
+}
+
+pub fn delete_task(client: &StewardxClient, id: &str, confirmation: &Confirmation) -> Result<()> {
+    if !confirm_single(client, TaskOperation::Delete, id, confirmation)? {
+        return Ok(());
+    }
+    let status = client.delete_task(id)?;
+    outln!("Task deletion status: {}", status);
+    Ok(())
+}
+
+pub fn execute_task(client: &StewardxClient, id: &str) -> Result<()> {
+    let status = client.execute_task(id)?;
+    outln!("Task execution status: {}", status);
+    Ok(())
+}
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Executes the task and blocks until its report arrives, aborting the task if it takes longer
+/// than the timeout. Status messages go to stderr so that stdout only has the task's output.
+pub fn execute_task_and_wait(
+    client: &StewardxClient,
+    id: &str,
+    timeout: Option<Duration>,
+    options: &OutputOptions,
+) -> Result<()> {
+    let previous_reports = client
+        .reports_for_task(id)?
+        .into_iter()
+        .map(|r| r.id)
+        .collect::<HashSet<String>>();
+    let status = client.execute_task(id)?;
+    eprintln!("Task execution status: {}", status);
+    let started_at = Instant::now();
+    // The task may not show up as active right away, so a new report is what marks it as done
+    let new_report = || -> Result<Option<Report>> {
+        Ok(client
+            .reports_for_task(id)?
+            .into_iter()
+            .filter(|r| !previous_reports.contains(&r.id))
+            .max_by_key(|r| r.created_at))
+    };
+    let report = loop {
+        let is_active = client.active_tasks()?.iter().any(|t| t.id == id);
+        if !is_active {
+            if let Some(report) = new_report()? {
+                break report;
+            }
+        }
+        if let Some(timeout) = timeout {
+            if started_at.elapsed() >= timeout {
+                // It may have finished since the last poll, then there's nothing to abort
+                if let Some(report) = new_report()? {
+                    break report;
                 }
-                Err(e) => {
-                    print_json_failure(e);
-                    process::exit(1);
+                // Timing out is what matters here, a failed abort doesn't change the exit code
+                match client.abort_task(id) {
+                    Ok(status) => eprintln!("Task abortion status: {}", status),
+                    Err(e) => eprintln!("Couldn't abort the task: {}", e),
                 }
-            };
-        }
-        Err(e) => {
-            print_stewardx_connection_failure(e);
-            process::exit(1);
+                return Err(Error::ExecutionTimedOut { task_id: id.to_string() });
+            }
         }
+        thread::sleep(POLL_INTERVAL);
     };
+    if options.format.is_some() || options.is_custom() {
+        print_report(&report, options)?;
+    } else {
+        print_report_output(&report);
+    }
+    if report.successful {
+        Ok(())
+    } else {
+        Err(Error::TaskFailed {
+            task_id: id.to_string(),
+            report_id: report.id,
+        })
+    }
 }
 
-pub fn abort_task(id: &str) {
-    let url = format!("{}/abort", get_stewardx_url());
-    let request = Request::builder()
-        .uri(url)
-        .method(isahc::http::Method::POST)
-        .body(serde_json::json!({ "task_id": id }).to_string())
-        .unwrap();
-    let response = request.send();
-    match response {
-        Ok(mut r) => {
-            let result: SerdeResult<Value> = r.json();
-            match result {
-                Ok(r) => {
-                    let status = &r["status"];
-                    if let Some(status) = status.as_str() {
-                        println!("Task abortion status: {}", status);
-                    } else {
-                        println!("Task abortion is failed, please check StewardX logs.");
-                    }
-                }
-                Err(e) => {
-                    print_json_failure(e);
-                    process::exit(1);
-                }
-            };
+pub fn abort_task(client: &StewardxClient, id: &str, confirmation: &Confirmation) -> Result<()> {
+    if !confirm_single(client, TaskOperation::Abort, id, confirmation)? {
+        return Ok(());
+    }
+    let status = client.abort_task(id)?;
+    outln!("Task abortion status: {}", status);
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskOperation {
+    Delete,
+    Execute,
+    Abort,
+}
+
+impl TaskOperation {
+    pub fn verb(self) -> &'static str {
+        match self {
+            TaskOperation::Delete => "delete",
+            TaskOperation::Execute => "execute",
+            TaskOperation::Abort => "abort",
         }
-        Err(e) => {
-            print_stewardx_connection_failure(e);
-            process::exit(1);
+    }
+
+    pub fn request(self, client: &StewardxClient, id: &str) -> PreparedRequest {
+        match self {
+            TaskOperation::Delete => client.delete_task_request(id),
+            TaskOperation::Execute => client.execute_task_request(id),
+            TaskOperation::Abort => client.abort_task_request(id),
         }
-    };
+    }
+
+    pub fn run(self, client: &StewardxClient, id: &str) -> Result<String> {
+        match self {
+            TaskOperation::Delete => client.delete_task(id),
+            TaskOperation::Execute => client.execute_task(id),
+            TaskOperation::Abort => client.abort_task(id),
+        }
+    }
+}
+
+/// Outcome of a bulk operation on a single task
+#[derive(Serialize, Clone, Debug)]
+pub struct BulkResult {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
-pub fn create_task(task_type: &str, name: &str, frequency: &str, props: &Value) {
-    let url = format!("{}/tasks", get_stewardx_url());
-    let request = Request::builder()
-        .uri(url)
-        .method(isahc::http::Method::POST)
-        .body(
-            serde_json::json!({
-                "task_type": task_type,
-                "task_name": name,
-                "frequency": frequency,
-                "task_props": props
+/// Runs the operation on every selected task and prints how each went. Without ids every task
+/// is selected, or every active one for aborting, and the filter narrows them down either way.
+/// Failing tasks don't stop the rest, the first failure is returned at the end.
+pub fn run_bulk(
+    client: &StewardxClient,
+    operation: TaskOperation,
+    ids: &[String],
+    filter: &TaskFilter,
+    confirmation: &Confirmation,
+    options: &OutputOptions,
+) -> Result<()> {
+    let tasks = match operation {
+        TaskOperation::Abort if ids.is_empty() => client.active_tasks()?,
+        _ => client.tasks()?,
+    };
+    let selected = if ids.is_empty() {
+        filter.apply(tasks).into_iter().map(|t| (t.id.clone(), Some(t))).collect()
+    } else {
+        // Unknown ids are still sent, so StewardX's answer for them shows up in the results
+        ids.iter()
+            .filter_map(|id| match tasks.iter().find(|t| t.id == *id) {
+                Some(task) if filter.matches(task) => Some((task.id.clone(), Some(task.clone()))),
+                Some(_) => None,
+                None => Some((id.to_string(), None)),
             })
-            .to_string(),
-        )
-        .unwrap();
-    let response = request.send();
-    match response {
-        Ok(mut r) => {
-            let result: SerdeResult<Value> = r.json();
-            match result {
-                Ok(r) => {
-                    pretty_print_tasks(vec![r]);
-                }
-                Err(e) => {
-                    print_json_failure(e);
-                    process::exit(1);
-                }
-            };
-        }
-        Err(e) => {
-            print_stewardx_connection_failure(e);
-            process::exit(1);
-        }
+            .collect::<Vec<(String, Option<Task>)>>()
     };
+    if selected.is_empty() {
+        eprintln!("No tasks matched");
+        return Ok(());
+    }
+    if confirmation.dry_run {
+        let requests = selected
+            .iter()
+            .map(|(id, _)| operation.request(client, id).to_string())
+            .collect::<Vec<String>>();
+        outln!("{}", requests.join("\n\n"));
+        return Ok(());
+    }
+    if !confirmation.yes && !confirm(operation, &selected)? {
+        eprintln!("Cancelled, nothing was sent");
+        return Ok(());
+    }
+    let mut first_error = None;
+    let results = selected
+        .into_iter()
+        .map(|(id, task)| (task.map(|t| t.task_name).unwrap_or_default(), id))
+        .map(|(name, id)| match operation.run(client, &id) {
+            Ok(status) => BulkResult {
+                id,
+                name,
+                status: Some(status),
+                error: None,
+            },
+            Err(e) => {
+                let error = e.to_string();
+                first_error.get_or_insert(e);
+                BulkResult {
+                    id,
+                    name,
+                    status: None,
+                    error: Some(error),
+                }
+            }
+        })
+        .collect::<Vec<BulkResult>>();
+    print_bulk_results(&results, options)?;
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
+    eprintln!("{} succeeded, {} failed", results.len() - failed, failed);
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
 }
 
-pub fn get_reports_for_task(id: &str) {
-    let url = format!("{}/task/{}/reports", get_stewardx_url(), id);
-    let reports: Result<SerdeResult<Value>, isahc::Error> = get(url).map(|mut t| t.json());
-    match reports {
-        Ok(result) => {
-            match result {
-                Ok(r) => {
-                    let reports = r.as_array().map(|v| v.to_owned()).unwrap_or(Vec::new());
-                    pretty_print_reports(reports);
-                    // println!("{}", r);
-                }
-                Err(e) => {
-                    print_json_failure(e);
-                    process::exit(1);
-                }
-            };
-        }
-        Err(e) => {
-            print_stewardx_connection_failure(e);
-            process::exit(1);
-        }
-    };
+pub fn create_task(client: &StewardxClient, task: &NewTask, options: &OutputOptions) -> Result<()> {
+    let task = client.create_task(task)?;
+    print_changed_task(&task, options)
+}
+
+/// What `tasks update` was asked to change, fields that aren't set are kept as they are
+#[derive(Clone, Debug, Default)]
+pub struct TaskChanges {
+    pub name: Option<String>,
+    pub frequency: Option<String>,
+    pub command: Option<String>,
+    pub docker_type: Option<DockerImageType>,
+    pub contents: Option<String>,
+    pub env: Option<Vec<String>>,
 }
 
-pub fn get_latest_reports() {
-    let url = format!("{}/reports", get_stewardx_url());
-    let reports: Result<SerdeResult<Value>, isahc::Error> = get(url).map(|mut t| t.json());
-    match reports {
-        Ok(result) => {
-            match result {
-                Ok(r) => {
-                    let reports = r.as_array().map(|v| v.to_owned()).unwrap_or(Vec::new());
-                    pretty_print_reports(reports);
-                    // println!("{}", r);
+fn wrong_task_type(field: &'static str, task: &Task) -> Error {
+    Error::InvalidTaskField {
+        field,
+        message: format!("task {} is a {} and doesn't have this field", task.id, task.task_type),
+    }
+}
+
+impl TaskChanges {
+    fn changes_docker_props(&self) -> bool {
+        self.contents.is_some() || self.env.is_some()
+    }
+
+    /// Builds an update with only the fields that differ from the task
+    fn to_update(&self, task: &Task) -> Result<TaskUpdate> {
+        let task_name = self.name.clone().filter(|name| name != &task.task_name);
+        let frequency = self
+            .frequency
+            .as_deref()
+            .map(parse_frequency)
+            .transpose()?
+            .filter(|frequency| frequency != &task.frequency);
+        let task_props = match &task.task_props {
+            TaskProps::Cmd(_) => {
+                if self.changes_docker_props() {
+                    return Err(wrong_task_type(if self.contents.is_some() { "contents" } else { "env" }, task));
                 }
-                Err(e) => {
-                    print_json_failure(e);
-                    process::exit(1);
+                self.command
+                    .clone()
+                    .map(|command| TaskProps::Cmd(CmdTaskProps { command }))
+            }
+            TaskProps::Docker(docker) => {
+                if self.command.is_some() {
+                    return Err(wrong_task_type("command", task));
                 }
-            };
-        }
-        Err(e) => {
-            print_stewardx_connection_failure(e);
-            process::exit(1);
-        }
-    };
+                let mut docker = docker.clone();
+                if let Some(contents) = &self.contents {
+                    docker.image = docker_image(self.docker_type.unwrap_or(docker.image.t), contents)?;
+                }
+                if let Some(env) = &self.env {
+                    docker.env = env.clone();
+                }
+                Some(TaskProps::Docker(docker))
+            }
+            TaskProps::Other(_) => {
+                if self.command.is_some() || self.changes_docker_props() {
+                    return Err(Error::InvalidTaskField {
+                        field: "task_props",
+                        message: format!("props of task {} aren't supported by this version", task.id),
+                    });
+                }
+                None
+            }
+        };
+        Ok(TaskUpdate {
+            task_name,
+            frequency,
+            task_props: task_props.filter(|props| props != &task.task_props),
+        })
+    }
 }
 
-pub fn get_report(id: &str) {
-    let url = format!("{}/reports/{}", get_stewardx_url(), id);
-    let reports: Result<SerdeResult<Value>, isahc::Error> = get(url).map(|mut t| t.json());
-    match reports {
-        Ok(result) => {
-            match result {
-                Ok(r) => match serde_json::to_string_pretty(&r) {
-                    Ok(v) => {
-                        println!("{}", v);
-                    }
-                    Err(e) => {
-                        print_json_failure(e);
-                        process::exit(1);
-                    }
-                },
-                Err(e) => {
-                    print_json_failure(e);
-                    process::exit(1);
-                }
-            };
+pub fn update_task(
+    client: &StewardxClient,
+    id: &str,
+    changes: &TaskChanges,
+    options: &OutputOptions,
+) -> Result<()> {
+    let task = client.task(id)?;
+    let update = changes.to_update(&task)?;
+    if update.is_empty() {
+        eprintln!("Nothing to update, task {} already has the given values", id);
+        return print_changed_task(&task, options);
+    }
+    let task = client.update_task(id, &update)?;
+    print_changed_task(&task, options)
+}
+
+/// Sets the variables given as KEY=VALUE, replacing the ones that are already set
+fn merge_env(env: &mut Vec<String>, overrides: &[String]) -> Result<()> {
+    for variable in overrides {
+        let key = match variable.split_once('=') {
+            Some((key, _)) if !key.is_empty() => key,
+            _ => {
+                return Err(Error::InvalidArgument {
+                    name: "env",
+                    message: format!("\"{}\" isn't like KEY=VALUE", variable),
+                })
+            }
+        };
+        match env.iter_mut().find(|e| e.split_once('=').map(|(k, _)| k) == Some(key)) {
+            Some(existing) => *existing = variable.clone(),
+            None => env.push(variable.clone()),
         }
-        Err(e) => {
-            print_stewardx_connection_failure(e);
-            process::exit(1);
+    }
+    Ok(())
+}
+
+/// Creates a copy of the task under a new name, optionally with another frequency or
+/// with extra environment variables for Docker tasks
+pub fn clone_task(
+    client: &StewardxClient,
+    id: &str,
+    name: &str,
+    frequency: Option<&str>,
+    env: &[String],
+    options: &OutputOptions,
+) -> Result<()> {
+    let source = client.task(id)?;
+    let mut task = NewTask::from(&source);
+    task.task_name = name.to_string();
+    if let Some(frequency) = frequency {
+        task.frequency = parse_frequency(frequency)?;
+    }
+    if !env.is_empty() {
+        match &mut task.task_props {
+            TaskProps::Docker(docker) => merge_env(&mut docker.env, env)?,
+            _ => return Err(wrong_task_type("env", &source)),
         }
+    }
+    let task = client.create_task(&task)?;
+    print_changed_task(&task, options)
+}
+
+pub fn get_reports_for_task(
+    client: &StewardxClient,
+    id: &str,
+    filter: &ReportFilter,
+    options: &OutputOptions,
+) -> Result<()> {
+    let reports = filter.apply(client.reports_for_task(id)?);
+    print_reports(&reports, options)
+}
+
+pub fn get_latest_reports(
+    client: &StewardxClient,
+    filter: &ReportFilter,
+    options: &OutputOptions,
+) -> Result<()> {
+    let reports = filter.apply(client.latest_reports()?);
+    print_reports(&reports, options)
+}
+
+fn fetch_reports(client: &StewardxClient, task: Option<&str>) -> Result<Vec<Report>> {
+    match task {
+        Some(id) => client.reports_for_task(id),
+        None => client.latest_reports(),
+    }
+}
+
+/// Prints the current reports, then polls for new ones until the process is interrupted.
+/// Reports are told apart by id, so ones that show up again on later polls aren't repeated.
+pub fn follow_reports(
+    client: &StewardxClient,
+    task: Option<&str>,
+    interval: Duration,
+    filter: &ReportFilter,
+    options: &OutputOptions,
+) -> Result<()> {
+    let mut stream = ReportStream::new(options);
+    let reports = fetch_reports(client, task)?;
+    let mut seen = reports.iter().map(|r| r.id.clone()).collect::<HashSet<String>>();
+    let mut reports = filter.apply(reports);
+    reports.sort_by_key(|r| r.created_at);
+    stream.print(&reports)?;
+    loop {
+        thread::sleep(interval);
+        let mut reports = fetch_reports(client, task)?
+            .into_iter()
+            .filter(|r| seen.insert(r.id.clone()) && filter.matches(r))
+            .collect::<Vec<Report>>();
+        reports.sort_by_key(|r| r.created_at);
+        stream.print(&reports)?;
+    }
+}
+
+pub fn get_report(client: &StewardxClient, id: &str, options: &OutputOptions) -> Result<()> {
+    let report = client.report(id)?;
+    print_report(&report, options)
+}
+
+pub fn show_report(client: &StewardxClient, id: &str, raw: bool, options: &OutputOptions) -> Result<()> {
+    let report = client.report(id)?;
+    if raw {
+        print_report_output(&report);
+        return Ok(());
+    }
+    // The task may have been deleted since, the report is still worth showing then
+    let task = match client.task(&report.task_id) {
+        Ok(task) => Some(task),
+        Err(Error::NotFound { .. }) => None,
+        Err(e) => return Err(e),
     };
+    print_report_details(&report, task.as_ref(), options)
 }
@@ -0,0 +1,348 @@
+use std::{fmt, time::Duration};
+
+use isahc::{config::Configurable, http::Method, prelude::*, Body, Request, Response};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{
+    error::{Error, Result},
+    models::{NewTask, Report, Task, TaskUpdate},
+};
+
+/// A request to StewardX, built before it's sent so it can be shown instead, like for a dry run
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreparedRequest {
+    pub method: Method,
+    pub url: String,
+    pub body: String,
+}
+
+impl fmt::Display for PreparedRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.url)?;
+        if !self.body.is_empty() {
+            write!(f, "\n\n{}", self.body)?;
+        }
+        Ok(())
+    }
+}
+
+/// How long a request can take before it fails with [`Error::Timeout`]
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug)]
+pub struct StewardxClient {
+    base_url: String,
+    timeout: Duration,
+}
+
+impl StewardxClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let base_url = base_url.into();
+        let base_url = base_url.trim_end_matches('/').to_string();
+        Self {
+            base_url,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Sets how long each request can take, [`DEFAULT_TIMEOUT`] unless it's changed
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Reads the instance's address from STEWARDX_URL, or from STEWARDX_HOST and STEWARDX_PORT.
+    /// STEWARDX_TIMEOUT overrides the request timeout, in seconds.
+    pub fn from_env() -> Self {
+        let url = match std::env::var("STEWARDX_URL") {
+            Ok(url) => url,
+            Err(_) => {
+                let host = std::env::var("STEWARDX_HOST").unwrap_or_else(|_| "localhost".into());
+                let port = std::env::var("STEWARDX_PORT").unwrap_or_else(|_| "3000".into());
+                format!("http://{}:{}", host, port)
+            }
+        };
+        let timeout = std::env::var("STEWARDX_TIMEOUT")
+            .ok()
+            .and_then(|seconds| seconds.trim().parse::<f64>().ok())
+            .filter(|seconds| seconds.is_finite() && *seconds > 0.0)
+            .map(Duration::from_secs_f64)
+            .unwrap_or(DEFAULT_TIMEOUT);
+        Self::new(url).with_timeout(timeout)
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path)
+    }
+
+    fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.send(self.prepare(Method::GET, path, String::new()))
+    }
+
+    fn prepare(&self, method: Method, path: &str, body: String) -> PreparedRequest {
+        PreparedRequest {
+            method,
+            url: self.url(path),
+            body,
+        }
+    }
+
+    fn send<T: DeserializeOwned>(&self, request: PreparedRequest) -> Result<T> {
+        let request = Request::builder()
+            .uri(request.url)
+            .method(request.method)
+            .timeout(self.timeout)
+            .body(request.body)
+            .map_err(isahc::Error::from)?;
+        let response = request.send()?;
+        read_response(response)
+    }
+
+    fn send_for_status(&self, operation: &'static str, request: PreparedRequest) -> Result<String> {
+        let response: Value = self.send(request)?;
+        match response["status"].as_str() {
+            Some(status) => Ok(status.to_string()),
+            None => Err(Error::OperationFailed { operation }),
+        }
+    }
+
+    pub fn tasks(&self) -> Result<Vec<Task>> {
+        self.get("tasks")
+    }
+
+    pub fn active_tasks(&self) -> Result<Vec<Task>> {
+        self.get("activetasks")
+    }
+
+    pub fn task(&self, id: &str) -> Result<Task> {
+        self.get(&format!("tasks/{}", id))
+            .map_err(|e| not_found_as(e, "task", id))
+    }
+
+    /// Finds a task's full id from a unique prefix of it, like a short git hash, or from
+    /// `name:<task name>`. Full ids are returned as they are, without asking StewardX.
+    pub fn resolve_task_id(&self, query: &str) -> Result<String> {
+        Ok(self.resolve_task_ids(&[query])?.remove(0))
+    }
+
+    /// Same as [`StewardxClient::resolve_task_id`] for several ids, the tasks are fetched once
+    pub fn resolve_task_ids(&self, queries: &[&str]) -> Result<Vec<String>> {
+        if queries.iter().all(|query| is_full_id(query)) {
+            return Ok(queries.iter().map(|query| query.to_string()).collect());
+        }
+        let tasks = self.tasks()?;
+        queries.iter().map(|query| resolve_task_id(query, &tasks)).collect()
+    }
+
+    pub fn create_task_request(&self, task: &NewTask) -> Result<PreparedRequest> {
+        let body = serde_json::to_string(task)?;
+        Ok(self.prepare(Method::POST, "tasks", body))
+    }
+
+    pub fn create_task(&self, task: &NewTask) -> Result<Task> {
+        self.send(self.create_task_request(task)?)
+    }
+
+    pub fn update_task_request(&self, id: &str, update: &TaskUpdate) -> Result<PreparedRequest> {
+        let body = serde_json::to_string(update)?;
+        Ok(self.prepare(Method::PUT, &format!("tasks/{}", id), body))
+    }
+
+    /// Changes only the fields that are set in the update, the task keeps its id and reports
+    pub fn update_task(&self, id: &str, update: &TaskUpdate) -> Result<Task> {
+        self.send(self.update_task_request(id, update)?)
+            .map_err(|e| not_found_as(e, "task", id))
+    }
+
+    pub fn delete_task_request(&self, id: &str) -> PreparedRequest {
+        let body = serde_json::json!({ "task_id": id }).to_string();
+        self.prepare(Method::DELETE, "tasks", body)
+    }
+
+    /// Returns the status message StewardX responded with
+    pub fn delete_task(&self, id: &str) -> Result<String> {
+        self.send_for_status("deletion", self.delete_task_request(id))
+            .map_err(|e| not_found_as(e, "task", id))
+    }
+
+    pub fn execute_task_request(&self, id: &str) -> PreparedRequest {
+        self.prepare(Method::POST, &format!("execute/{}", id), String::new())
+    }
+
+    /// Returns the status message StewardX responded with
+    pub fn execute_task(&self, id: &str) -> Result<String> {
+        self.send_for_status("execution", self.execute_task_request(id))
+            .map_err(|e| not_found_as(e, "task", id))
+    }
+
+    pub fn abort_task_request(&self, id: &str) -> PreparedRequest {
+        let body = serde_json::json!({ "task_id": id }).to_string();
+        self.prepare(Method::POST, "abort", body)
+    }
+
+    /// Returns the status message StewardX responded with
+    pub fn abort_task(&self, id: &str) -> Result<String> {
+        self.send_for_status("abortion", self.abort_task_request(id))
+            .map_err(|e| not_found_as(e, "task", id))
+    }
+
+    pub fn latest_reports(&self) -> Result<Vec<Report>> {
+        self.get("reports")
+    }
+
+    pub fn reports_for_task(&self, task_id: &str) -> Result<Vec<Report>> {
+        self.get(&format!("task/{}/reports", task_id))
+            .map_err(|e| not_found_as(e, "task", task_id))
+    }
+
+    pub fn report(&self, id: &str) -> Result<Report> {
+        self.get(&format!("reports/{}", id))
+            .map_err(|e| not_found_as(e, "report", id))
+    }
+}
+
+fn read_response<T: DeserializeOwned>(mut response: Response<Body>) -> Result<T> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response.json()?);
+    }
+    let body = response.text().unwrap_or_default();
+    let message = error_message(&body)
+        .unwrap_or_else(|| status.canonical_reason().unwrap_or("unknown error").to_string());
+    log::debug!("StewardX responded with {}: {}", status, body);
+    let status = status.as_u16();
+    if (500..600).contains(&status) {
+        Err(Error::ServerError { status, message })
+    } else {
+        Err(Error::ClientError { status, message })
+    }
+}
+
+/// Extracts the message from an error body, which StewardX sends either as JSON or plain text
+fn error_message(body: &str) -> Option<String> {
+    let body = body.trim();
+    if body.is_empty() {
+        return None;
+    }
+    if let Ok(value) = serde_json::from_str::<Value>(body) {
+        for key in &["error", "message", "status"] {
+            if let Some(message) = value[key].as_str() {
+                return Some(message.to_string());
+            }
+        }
+    }
+    Some(body.to_string())
+}
+
+/// Whether this is a complete task id, a UUID like 517e2f5a-16d8-4870-800a-d453512ddb27
+fn is_full_id(id: &str) -> bool {
+    id.len() == 36 && id.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+}
+
+fn resolve_task_id(query: &str, tasks: &[Task]) -> Result<String> {
+    // Full ids that aren't in the list are still sent, StewardX answers for them
+    if is_full_id(query) {
+        return Ok(query.to_string());
+    }
+    let matches = match query.strip_prefix("name:") {
+        Some(name) => tasks.iter().filter(|t| t.task_name == name).collect::<Vec<&Task>>(),
+        None if query.is_empty() => Vec::new(),
+        None => {
+            let prefix = query.to_lowercase();
+            tasks.iter().filter(|t| t.id.starts_with(&prefix)).collect()
+        }
+    };
+    match matches.as_slice() {
+        [task] => Ok(task.id.clone()),
+        [] => Err(Error::NotFound {
+            resource: "task",
+            id: query.to_string(),
+        }),
+        _ => Err(Error::AmbiguousTask {
+            query: query.to_string(),
+            matches: matches.iter().map(|t| (t.id.clone(), t.task_name.clone())).collect(),
+        }),
+    }
+}
+
+fn not_found_as(e: Error, resource: &'static str, id: &str) -> Error {
+    match e {
+        Error::ClientError { status: 404, .. } => Error::NotFound {
+            resource,
+            id: id.to_string(),
+        },
+        e => e,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve_task_id;
+    use crate::{
+        error::Error,
+        models::{CmdTaskProps, Task, TaskProps, TaskType},
+    };
+
+    fn task(id: &str, name: &str) -> Task {
+        Task {
+            id: id.to_string(),
+            task_name: name.to_string(),
+            task_type: TaskType::CmdTask,
+            frequency: "Hook".to_string(),
+            task_props: TaskProps::Cmd(CmdTaskProps {
+                command: "true".to_string(),
+            }),
+            exec_count: None,
+            last_execution: None,
+            next_execution: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    fn tasks() -> Vec<Task> {
+        vec![
+            task("517e2f5a-16d8-4870-800a-d453512ddb27", "backup-db"),
+            task("51a0c9e2-7b1d-4c6e-9f3a-2d8e4b6a1c05", "cleanup"),
+            task("9c3b1d4e-5f6a-4b7c-8d9e-0a1b2c3d4e5f", "cleanup"),
+        ]
+    }
+
+    #[test]
+    fn resolves_unique_prefixes() {
+        let tasks = tasks();
+        assert_eq!(resolve_task_id("517", &tasks).unwrap(), tasks[0].id);
+        assert_eq!(resolve_task_id("9C3B", &tasks).unwrap(), tasks[2].id);
+        assert_eq!(resolve_task_id("name:backup-db", &tasks).unwrap(), tasks[0].id);
+    }
+
+    #[test]
+    fn rejects_ambiguous_queries() {
+        let tasks = tasks();
+        match resolve_task_id("51", &tasks) {
+            Err(Error::AmbiguousTask { matches, .. }) => assert_eq!(matches.len(), 2),
+            other => panic!("expected an ambiguous task, got {:?}", other),
+        }
+        match resolve_task_id("name:cleanup", &tasks) {
+            Err(Error::AmbiguousTask { matches, .. }) => assert_eq!(matches.len(), 2),
+            other => panic!("expected an ambiguous task, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_empty_and_unknown_queries() {
+        let tasks = tasks();
+        assert!(matches!(resolve_task_id("", &tasks), Err(Error::NotFound { .. })));
+        assert!(matches!(resolve_task_id("ff", &tasks), Err(Error::NotFound { .. })));
+        assert!(matches!(resolve_task_id("name:", &tasks), Err(Error::NotFound { .. })));
+    }
+}
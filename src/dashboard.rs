@@ -0,0 +1,353 @@
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{Block, Paragraph, Row, Table, TableState, Wrap},
+    DefaultTerminal, Frame,
+};
+use stewardx_cli::{Error, Report, Result, StewardxClient, Task};
+
+use crate::{
+    api::TaskOperation,
+    output::format_task_props,
+    utils::{format_date, format_duration},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Pane {
+    Tasks,
+    Active,
+    Reports,
+}
+
+impl Pane {
+    fn next(self) -> Self {
+        match self {
+            Pane::Tasks => Pane::Active,
+            Pane::Active => Pane::Reports,
+            Pane::Reports => Pane::Tasks,
+        }
+    }
+
+    fn previous(self) -> Self {
+        match self {
+            Pane::Tasks => Pane::Reports,
+            Pane::Active => Pane::Tasks,
+            Pane::Reports => Pane::Active,
+        }
+    }
+}
+
+struct Dashboard<'a> {
+    client: &'a StewardxClient,
+    tasks: Vec<Task>,
+    active_tasks: Vec<Task>,
+    reports: Vec<Report>,
+    focus: Pane,
+    tasks_state: TableState,
+    active_state: TableState,
+    reports_state: TableState,
+    output_scroll: u16,
+    /// Deletion or abort waiting for the user to confirm it
+    pending: Option<(TaskOperation, Task)>,
+    status: String,
+    refreshed_at: Instant,
+}
+
+impl<'a> Dashboard<'a> {
+    fn new(client: &'a StewardxClient) -> Result<Self> {
+        let mut dashboard = Dashboard {
+            client,
+            tasks: Vec::new(),
+            active_tasks: Vec::new(),
+            reports: Vec::new(),
+            focus: Pane::Tasks,
+            tasks_state: TableState::default(),
+            active_state: TableState::default(),
+            reports_state: TableState::default(),
+            output_scroll: 0,
+            pending: None,
+            status: String::new(),
+            refreshed_at: Instant::now(),
+        };
+        dashboard.refresh()?;
+        Ok(dashboard)
+    }
+
+    fn refresh(&mut self) -> Result<()> {
+        self.refreshed_at = Instant::now();
+        self.tasks = self.client.tasks()?;
+        self.active_tasks = self.client.active_tasks()?;
+        let mut reports = self.client.latest_reports()?;
+        reports.sort_by_key(|r| std::cmp::Reverse(r.created_at));
+        self.reports = reports;
+        clamp_selection(&mut self.tasks_state, self.tasks.len());
+        clamp_selection(&mut self.active_state, self.active_tasks.len());
+        clamp_selection(&mut self.reports_state, self.reports.len());
+        Ok(())
+    }
+
+    /// Refreshes, keeping the dashboard up with the error in the status line if it fails
+    fn refresh_or_report(&mut self) {
+        if let Err(e) = self.refresh() {
+            self.status = format!("Refresh failed: {}", e);
+        }
+    }
+
+    fn focused_state(&mut self) -> (&mut TableState, usize) {
+        match self.focus {
+            Pane::Tasks => (&mut self.tasks_state, self.tasks.len()),
+            Pane::Active => (&mut self.active_state, self.active_tasks.len()),
+            Pane::Reports => (&mut self.reports_state, self.reports.len()),
+        }
+    }
+
+    fn move_selection(&mut self, by: isize) {
+        let (state, len) = self.focused_state();
+        if len == 0 {
+            return;
+        }
+        let selected = state.selected().unwrap_or(0) as isize + by;
+        state.select(Some(selected.clamp(0, len as isize - 1) as usize));
+        if self.focus == Pane::Reports {
+            self.output_scroll = 0;
+        }
+    }
+
+    fn selected_report(&self) -> Option<&Report> {
+        self.reports_state.selected().and_then(|i| self.reports.get(i))
+    }
+
+    /// The task under the cursor, a report selects the task it belongs to
+    fn selected_task(&self) -> Option<Task> {
+        let id = match self.focus {
+            Pane::Tasks => self.tasks_state.selected().and_then(|i| self.tasks.get(i)).map(|t| &t.id),
+            Pane::Active => self
+                .active_state
+                .selected()
+                .and_then(|i| self.active_tasks.get(i))
+                .map(|t| &t.id),
+            Pane::Reports => self.selected_report().map(|r| &r.task_id),
+        }?;
+        self.tasks
+            .iter()
+            .chain(&self.active_tasks)
+            .find(|t| &t.id == id)
+            .cloned()
+    }
+
+    fn run_on_task(&mut self, operation: TaskOperation, task: &Task) {
+        self.status = match operation.run(self.client, &task.id) {
+            Ok(status) => format!("{} {}: {}", past_tense(operation), task.task_name, status),
+            Err(e) => format!("Couldn't {} {}: {}", operation.verb(), task.task_name, e),
+        };
+        self.refresh_or_report();
+    }
+
+    /// Asks before deleting or aborting the selected task, executing it happens right away
+    fn run_on_selected(&mut self, operation: TaskOperation) {
+        let task = match self.selected_task() {
+            Some(task) => task,
+            None => return,
+        };
+        if operation == TaskOperation::Execute {
+            self.run_on_task(operation, &task);
+        } else {
+            self.status = format!("Going to {} {} ({}), continue? y/n", operation.verb(), task.task_name, task.id);
+            self.pending = Some((operation, task));
+        }
+    }
+
+    /// Handles a key press, returns false when the dashboard should close
+    fn handle_key(&mut self, key: KeyCode) -> bool {
+        if let Some((operation, task)) = self.pending.take() {
+            if key == KeyCode::Char('y') {
+                self.run_on_task(operation, &task);
+            } else {
+                self.status = format!("Cancelled, {} wasn't {}", task.task_name, past_tense(operation).to_lowercase());
+            }
+            return true;
+        }
+        // The status answers the previous key, the next one brings the key help back
+        self.status.clear();
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Tab => self.focus = self.focus.next(),
+            KeyCode::BackTab => self.focus = self.focus.previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::PageDown => self.output_scroll = self.output_scroll.saturating_add(10),
+            KeyCode::PageUp => self.output_scroll = self.output_scroll.saturating_sub(10),
+            KeyCode::Char('r') => {
+                self.status = "Refreshed".to_string();
+                self.refresh_or_report();
+            }
+            KeyCode::Char('x') => self.run_on_selected(TaskOperation::Execute),
+            KeyCode::Char('a') => self.run_on_selected(TaskOperation::Abort),
+            KeyCode::Char('d') => self.run_on_selected(TaskOperation::Delete),
+            _ => {}
+        }
+        true
+    }
+
+    fn task_name(&self, id: &str) -> String {
+        self.tasks
+            .iter()
+            .find(|t| t.id == id)
+            .map(|t| t.task_name.clone())
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    fn block(&self, title: &str, pane: Pane) -> Block<'static> {
+        let block = Block::bordered().title(format!(" {} ", title));
+        if self.focus == pane {
+            block.border_style(Style::new().fg(Color::Cyan))
+        } else {
+            block
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [top, bottom, footer] = Layout::vertical([
+            Constraint::Percentage(45),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [tasks_area, active_area] =
+            Layout::horizontal([Constraint::Percentage(65), Constraint::Fill(1)]).areas(top);
+        let [reports_area, output_area] =
+            Layout::horizontal([Constraint::Percentage(45), Constraint::Fill(1)]).areas(bottom);
+        self.draw_tasks(frame, tasks_area);
+        self.draw_active_tasks(frame, active_area);
+        self.draw_reports(frame, reports_area);
+        self.draw_output(frame, output_area);
+        let help = "tab: switch pane  j/k: move  x: execute  a: abort  d: delete  r: refresh  q: quit";
+        let footer_text = if self.status.is_empty() { help } else { &self.status };
+        frame.render_widget(Paragraph::new(footer_text).dim(), footer);
+    }
+
+    fn draw_tasks(&mut self, frame: &mut Frame, area: Rect) {
+        let active = |id: &str| self.active_tasks.iter().any(|t| t.id == id);
+        let rows = self.tasks.iter().map(|task| {
+            let state = if active(&task.id) { "running".green() } else { "".into() };
+            Row::new(vec![
+                task.task_name.clone().into(),
+                task.task_type.to_string().into(),
+                task.frequency.clone().into(),
+                format_task_props(&task.task_props).into(),
+                Line::from(state),
+            ])
+        });
+        let widths = [
+            Constraint::Fill(2),
+            Constraint::Length(10),
+            Constraint::Fill(2),
+            Constraint::Fill(3),
+            Constraint::Length(7),
+        ];
+        let table = Table::new(rows, widths)
+            .header(Row::new(["Name", "Type", "Frequency", "Props", "State"]).bold())
+            .block(self.block(&format!("Tasks ({})", self.tasks.len()), Pane::Tasks))
+            .row_highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(table, area, &mut self.tasks_state);
+    }
+
+    fn draw_active_tasks(&mut self, frame: &mut Frame, area: Rect) {
+        let now = chrono::Utc::now().naive_utc();
+        let rows = self.active_tasks.iter().map(|task| {
+            let running_for = task
+                .last_execution
+                .map(|started_at| format_duration(now - started_at))
+                .unwrap_or_default();
+            Row::new(vec![task.task_name.clone(), running_for])
+        });
+        let table = Table::new(rows, [Constraint::Fill(1), Constraint::Length(12)])
+            .header(Row::new(["Name", "Running for"]).bold())
+            .block(self.block(&format!("Active ({})", self.active_tasks.len()), Pane::Active))
+            .row_highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(table, area, &mut self.active_state);
+    }
+
+    fn draw_reports(&mut self, frame: &mut Frame, area: Rect) {
+        let rows = self
+            .reports
+            .iter()
+            .map(|report| {
+                let outcome = if report.successful { "success".green() } else { "failed".red() };
+                Row::new(vec![
+                    format_date(report.created_at).into(),
+                    self.task_name(&report.task_id).into(),
+                    Line::from(outcome),
+                ])
+            })
+            .collect::<Vec<_>>();
+        let widths = [Constraint::Length(23), Constraint::Fill(1), Constraint::Length(7)];
+        let table = Table::new(rows, widths)
+            .header(Row::new(["Executed At", "Task", "Status"]).bold())
+            .block(self.block("Latest reports", Pane::Reports))
+            .row_highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(table, area, &mut self.reports_state);
+    }
+
+    fn draw_output(&self, frame: &mut Frame, area: Rect) {
+        let (title, output) = match self.selected_report() {
+            Some(report) => (format!(" Output of {} ", report.id), report.output.as_str()),
+            None => (" Output ".to_string(), ""),
+        };
+        let paragraph = Paragraph::new(output)
+            .block(Block::bordered().title(title))
+            .wrap(Wrap { trim: false })
+            .scroll((self.output_scroll, 0));
+        frame.render_widget(paragraph, area);
+    }
+}
+
+fn past_tense(operation: TaskOperation) -> &'static str {
+    match operation {
+        TaskOperation::Delete => "Deleted",
+        TaskOperation::Execute => "Executed",
+        TaskOperation::Abort => "Aborted",
+    }
+}
+
+fn clamp_selection(state: &mut TableState, len: usize) {
+    match (state.selected(), len) {
+        (_, 0) => state.select(None),
+        (None, _) => state.select(Some(0)),
+        (Some(i), len) if i >= len => state.select(Some(len - 1)),
+        _ => {}
+    }
+}
+
+fn run(terminal: &mut DefaultTerminal, dashboard: &mut Dashboard, interval: Duration) -> std::io::Result<()> {
+    loop {
+        terminal.draw(|frame| dashboard.draw(frame))?;
+        let timeout = interval.saturating_sub(dashboard.refreshed_at.elapsed());
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !dashboard.handle_key(key.code) {
+                    return Ok(());
+                }
+            }
+        } else {
+            dashboard.refresh_or_report();
+        }
+    }
+}
+
+/// Opens the full screen dashboard and refreshes it every interval until the user quits
+pub fn show_dashboard(client: &StewardxClient, interval: Duration) -> Result<()> {
+    // Fetch before taking over the terminal so connection errors are printed as usual
+    let mut dashboard = Dashboard::new(client)?;
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut dashboard, interval);
+    ratatui::restore();
+    result.map_err(|source| Error::Io {
+        context: "couldn't draw the dashboard".into(),
+        source,
+    })
+}
@@ -0,0 +1,135 @@
+use crate::color::{paint, Stream, Style};
+
+/// Lines of unchanged context shown around each change
+const CONTEXT: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Line<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Line by line diff through the longest common subsequence, documents here are small enough
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Line<'a>> {
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::new();
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(Line::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            lines.push(Line::Removed(old[i]));
+            i += 1;
+        } else {
+            lines.push(Line::Added(new[j]));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().map(|l| Line::Removed(l)));
+    lines.extend(new[j..].iter().map(|l| Line::Added(l)));
+    lines
+}
+
+/// Ranges of diff lines that make up each hunk, changes closer than twice the context are merged
+fn hunks(lines: &[Line]) -> Vec<(usize, usize)> {
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if let Line::Same(_) = line {
+            continue;
+        }
+        let start = i.saturating_sub(CONTEXT);
+        let end = (i + CONTEXT + 1).min(lines.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+    hunks
+}
+
+/// Formats a unified diff between the two texts, it's empty when they're the same
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    let old_lines = old.lines().collect::<Vec<&str>>();
+    let new_lines = new.lines().collect::<Vec<&str>>();
+    let lines = diff_lines(&old_lines, &new_lines);
+    let hunks = hunks(&lines);
+    if hunks.is_empty() {
+        return String::new();
+    }
+    let mut out = vec![
+        paint(&format!("--- {}", old_label), Style::Bold, Stream::Stdout),
+        paint(&format!("+++ {}", new_label), Style::Bold, Stream::Stdout),
+    ];
+    for (start, end) in hunks {
+        // Line numbers of the hunk's first line in both texts
+        let old_start = lines[..start].iter().filter(|l| !matches!(l, Line::Added(_))).count();
+        let new_start = lines[..start].iter().filter(|l| !matches!(l, Line::Removed(_))).count();
+        let hunk = &lines[start..end];
+        let old_count = hunk.iter().filter(|l| !matches!(l, Line::Added(_))).count();
+        let new_count = hunk.iter().filter(|l| !matches!(l, Line::Removed(_))).count();
+        let header = format!(
+            "@@ -{},{} +{},{} @@",
+            old_start + (old_count > 0) as usize,
+            old_count,
+            new_start + (new_count > 0) as usize,
+            new_count
+        );
+        out.push(paint(&header, Style::Cyan, Stream::Stdout));
+        for line in hunk {
+            out.push(match line {
+                Line::Same(l) => format!(" {}", l),
+                Line::Removed(l) => paint(&format!("-{}", l), Style::Red, Stream::Stdout),
+                Line::Added(l) => paint(&format!("+{}", l), Style::Green, Stream::Stdout),
+            });
+        }
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::unified_diff;
+    use crate::color::{set_color_choice, ColorChoice};
+
+    fn diff(old: &str, new: &str) -> String {
+        set_color_choice(ColorChoice::Never);
+        unified_diff(old, new, "old", "new")
+    }
+
+    #[test]
+    fn shows_context_around_a_change() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni";
+        let new = "a\nb\nc\nd\nE\nf\ng\nh\ni";
+        let expected = "--- old\n+++ new\n@@ -2,7 +2,7 @@\n b\n c\n d\n-e\n+E\n f\n g\n h";
+        assert_eq!(diff(old, new), expected);
+        assert_eq!(diff(old, old), "");
+    }
+
+    #[test]
+    fn creates_from_and_deletes_to_nothing() {
+        assert_eq!(diff("", "a\nb"), "--- old\n+++ new\n@@ -0,0 +1,2 @@\n+a\n+b");
+        assert_eq!(diff("a\nb", ""), "--- old\n+++ new\n@@ -1,2 +0,0 @@\n-a\n-b");
+    }
+
+    #[test]
+    fn merges_close_changes_into_one_hunk() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl";
+        let new = "a\nB\nc\nd\ne\nf\ng\nH\ni\nj\nk\nl";
+        let expected = "--- old\n+++ new\n@@ -1,11 +1,11 @@\n a\n-b\n+B\n c\n d\n e\n f\n g\n-h\n+H\n i\n j\n k";
+        assert_eq!(diff(old, new), expected);
+        let far = "a\nB\nc\nd\ne\nf\ng\nh\ni\nJ\nk\nl";
+        assert_eq!(diff(old, far).matches("@@ -").count(), 2);
+    }
+}
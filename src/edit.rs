@@ -0,0 +1,125 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+    process::Command,
+};
+
+use stewardx_cli::{Error, NewTask, Result, StewardxClient, TaskUpdate};
+
+use crate::{
+    diff::unified_diff,
+    output::{print_changed_task, to_yaml, OutputOptions},
+    utils::validate_task,
+};
+
+const EDIT_HEADER: &str = "\
+# Please edit the task below. Lines beginning with '#' are ignored,
+# and an empty file aborts the edit.
+";
+
+/// Editor from VISUAL or EDITOR, falling back to vi
+fn editor() -> String {
+    std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string())
+}
+
+fn io_error(context: String) -> impl FnOnce(io::Error) -> Error {
+    move |source| Error::Io { context, source }
+}
+
+/// Opens the file in the editor and waits for it to close. The editor is run through the shell
+/// so that EDITOR can have arguments, like `code --wait`.
+fn run_editor(path: &Path) -> Result<()> {
+    let editor = editor();
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg("sh")
+        .arg(path)
+        .status()
+        .map_err(io_error(format!("couldn't run editor {}", editor)))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::Io {
+            context: format!("editor {} failed", editor),
+            source: io::Error::other(format!("it exited with {}", status)),
+        })
+    }
+}
+
+/// Writes the document to a new file in the temp directory. Its name is random and it's
+/// created exclusively, so a file or symlink someone else planted there can't be written through.
+fn write_temp_file(id: &str, document: &str) -> Result<tempfile::TempPath> {
+    let mut file = tempfile::Builder::new()
+        .prefix(&format!("stxctl-edit-{}-", id))
+        .suffix(".yaml")
+        .tempfile()
+        .map_err(io_error("couldn't create a temporary file to edit".into()))?;
+    file.write_all(document.as_bytes())
+        .map_err(io_error(format!("couldn't write {}", file.path().display())))?;
+    Ok(file.into_temp_path())
+}
+
+fn parse_edited(current: &NewTask, edited: &str) -> Result<NewTask> {
+    let desired = serde_yaml::from_str::<NewTask>(edited).map_err(|e| Error::InvalidTaskField {
+        field: "task",
+        message: e.to_string(),
+    })?;
+    if desired.task_type != current.task_type {
+        return Err(Error::InvalidTaskField {
+            field: "task_type",
+            message: "a task's type can't be changed, create a new task instead".into(),
+        });
+    }
+    validate_task(desired)
+}
+
+/// Opens the task as YAML in the user's editor and submits what changed, similar to `kubectl edit`.
+/// If the edited task is invalid or StewardX rejects it, the file is kept so the changes aren't lost.
+pub fn edit_task(client: &StewardxClient, id: &str, options: &OutputOptions) -> Result<()> {
+    let task = client.task(id)?;
+    let current = NewTask::from(&task);
+    let original = to_yaml(&current)?;
+    let document = format!("{}# Task {}\n{}", EDIT_HEADER, task.id, original);
+    // The file is removed when path is dropped, unless it's kept below
+    let path = write_temp_file(&task.id, &document)?;
+    run_editor(&path)?;
+    let edited =
+        fs::read_to_string(&path).map_err(io_error(format!("couldn't read {}", path.display())))?;
+    let is_empty = edited
+        .lines()
+        .all(|l| l.trim().is_empty() || l.trim_start().starts_with('#'));
+    if is_empty {
+        eprintln!("Edit cancelled, the file is empty");
+        return Ok(());
+    }
+    let submitted = parse_edited(&current, &edited).and_then(|desired| {
+        let update = TaskUpdate::between(&task, &desired);
+        if update.is_empty() {
+            return Ok(None);
+        }
+        let edited = to_yaml(&desired)?;
+        outln!("{}", unified_diff(&original, &edited, &format!("task/{}", task.id), "edited"));
+        client.update_task(&task.id, &update).map(Some)
+    });
+    let updated = match submitted {
+        Ok(updated) => updated,
+        Err(e) => {
+            if let Ok(kept) = path.keep() {
+                eprintln!("A copy of your changes has been saved to {}", kept.display());
+            }
+            return Err(e);
+        }
+    };
+    drop(path);
+    match updated {
+        Some(task) => print_changed_task(&task, options),
+        None => {
+            eprintln!("Edit cancelled, no changes made");
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use std::fmt;
+
+use isahc::error::ErrorKind;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Everything that can go wrong while talking to StewardX.
+///
+/// Each variant maps to its own process exit code, see [`Error::exit_code`].
+#[derive(Debug)]
+pub enum Error {
+    /// Couldn't reach the StewardX instance, the connection is refused or the host is unreachable.
+    Connection(isahc::Error),
+    /// StewardX didn't respond in time.
+    Timeout(isahc::Error),
+    /// StewardX responded with a 4xx status.
+    ClientError { status: u16, message: String },
+    /// StewardX responded with a 5xx status.
+    ServerError { status: u16, message: String },
+    /// The requested task or report doesn't exist.
+    NotFound { resource: &'static str, id: String },
+    /// StewardX responded with something we couldn't parse.
+    InvalidJson(serde_json::Error),
+    /// A task property supplied by the user is invalid.
+    InvalidTaskField { field: &'static str, message: String },
+    /// The supplied frequency isn't a valid cron string.
+    InvalidCron(String),
+    /// StewardX accepted the request but didn't report a status for it.
+    OperationFailed { operation: &'static str },
+    /// A command line argument couldn't be parsed.
+    InvalidArgument { name: &'static str, message: String },
+    /// The command can't be run the way it was called.
+    Usage(String),
+    /// A short id or task name matches more than one task, as (id, name) pairs.
+    AmbiguousTask { query: String, matches: Vec<(String, String)> },
+    /// The task ran but its report says it failed.
+    TaskFailed { task_id: String, report_id: String },
+    /// The task didn't finish in the given time and was aborted.
+    ExecutionTimedOut { task_id: String },
+    /// Something on this machine failed, like a temporary file, the editor or the terminal.
+    Io { context: String, source: std::io::Error },
+}
+
+impl Error {
+    /// Exit code the CLI terminates with when it fails with this error.
+    ///
+    /// | Code | Meaning                              |
+    /// |------|--------------------------------------|
+    /// | 1    | StewardX couldn't perform the action |
+    /// | 2    | Invalid command line usage           |
+    /// | 3    | Connection failed                    |
+    /// | 4    | Connection timed out                 |
+    /// | 5    | HTTP 4xx response                    |
+    /// | 6    | HTTP 5xx response                    |
+    /// | 7    | Task or report not found             |
+    /// | 8    | Invalid JSON response                |
+    /// | 9    | Invalid task field                   |
+    /// | 10   | Invalid cron string                  |
+    /// | 11   | Task execution failed                |
+    /// | 12   | Task execution timed out             |
+    /// | 13   | Local file, editor or terminal error |
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::OperationFailed { .. } => 1,
+            Error::InvalidArgument { .. } | Error::Usage(_) | Error::AmbiguousTask { .. } => 2,
+            Error::Connection(_) => 3,
+            Error::Timeout(_) => 4,
+            Error::ClientError { .. } => 5,
+            Error::ServerError { .. } => 6,
+            Error::NotFound { .. } => 7,
+            Error::InvalidJson(_) => 8,
+            Error::InvalidTaskField { .. } => 9,
+            Error::InvalidCron(_) => 10,
+            Error::TaskFailed { .. } => 11,
+            Error::ExecutionTimedOut { .. } => 12,
+            Error::Io { .. } => 13,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Connection(e) => write!(f, "couldn't connect to StewardX: {}", e),
+            Error::Timeout(e) => write!(f, "StewardX didn't respond in time: {}", e),
+            Error::ClientError { status, message } | Error::ServerError { status, message } => {
+                write!(f, "StewardX responded with status {}: {}", status, message)
+            }
+            Error::NotFound { resource, id } => write!(f, "{} not found: {}", resource, id),
+            Error::InvalidJson(e) => write!(f, "couldn't parse response from StewardX: {}", e),
+            Error::InvalidTaskField { field, message } => {
+                write!(f, "invalid value for \"{}\": {}", field, message)
+            }
+            Error::InvalidCron(message) => write!(f, "invalid cron string: {}", message),
+            Error::OperationFailed { operation } => {
+                write!(f, "task {} is failed, please check StewardX logs", operation)
+            }
+            Error::InvalidArgument { name, message } => {
+                write!(f, "invalid value for --{}: {}", name, message)
+            }
+            Error::Usage(message) => write!(f, "{}", message),
+            Error::AmbiguousTask { query, matches } => {
+                write!(f, "\"{}\" matches {} tasks:", query, matches.len())?;
+                for (id, name) in matches {
+                    write!(f, "\n  {}  {}", id, name)?;
+                }
+                if query.starts_with("name:") {
+                    write!(f, "\nuse the id of the one you mean")
+                } else {
+                    write!(f, "\nuse a longer prefix or the full id")
+                }
+            }
+            Error::TaskFailed { task_id, report_id } => {
+                write!(f, "task {} failed, see report {}", task_id, report_id)
+            }
+            Error::ExecutionTimedOut { task_id } => {
+                write!(f, "task {} didn't finish in time and was aborted", task_id)
+            }
+            Error::Io { context, source } => write!(f, "{}: {}", context, source),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Connection(e) | Error::Timeout(e) => Some(e),
+            Error::InvalidJson(e) => Some(e),
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<isahc::Error> for Error {
+    fn from(e: isahc::Error) -> Self {
+        match e.kind() {
+            ErrorKind::Timeout => Error::Timeout(e),
+            _ => Error::Connection(e),
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::InvalidJson(e)
+    }
+}
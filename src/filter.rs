@@ -0,0 +1,245 @@
+use std::{cmp::Ordering, str::FromStr};
+
+use chrono::NaiveDateTime;
+use clap::ArgMatches;
+use regex::Regex;
+use stewardx_cli::{Error, Report, Result, Task, TaskType};
+
+use crate::utils::parse_time;
+
+/// Matches task names either by a glob like backup-* or by a regex wrapped in slashes like /^backup/
+#[derive(Clone, Debug)]
+pub struct NamePattern(Regex);
+
+impl NamePattern {
+    pub fn parse(pattern: &str) -> std::result::Result<Self, String> {
+        let regex = if pattern.len() > 1 && pattern.starts_with('/') && pattern.ends_with('/') {
+            pattern[1..pattern.len() - 1].to_string()
+        } else {
+            glob_to_regex(pattern)
+        };
+        Regex::new(&regex).map(NamePattern).map_err(|e| e.to_string())
+    }
+
+    pub fn is_match(&self, name: &str) -> bool {
+        self.0.is_match(name)
+    }
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrequencyKind {
+    Hook,
+    Scheduled,
+}
+
+impl FromStr for FrequencyKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "hook" => Ok(FrequencyKind::Hook),
+            "scheduled" => Ok(FrequencyKind::Scheduled),
+            _ => Err(format!("unknown frequency kind: {}", s)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskSortKey {
+    Name,
+    Type,
+    Frequency,
+    Created,
+}
+
+impl FromStr for TaskSortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(TaskSortKey::Name),
+            "type" => Ok(TaskSortKey::Type),
+            "frequency" => Ok(TaskSortKey::Frequency),
+            "created" => Ok(TaskSortKey::Created),
+            _ => Err(format!("unknown sort key: {}", s)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TaskFilter {
+    pub task_type: Option<TaskType>,
+    pub name: Option<NamePattern>,
+    pub frequency: Option<FrequencyKind>,
+    pub sort: Option<TaskSortKey>,
+    pub reverse: bool,
+}
+
+fn parse_arg<T: FromStr<Err = String>>(matches: &ArgMatches, name: &'static str) -> Result<Option<T>> {
+    matches
+        .value_of(name)
+        .map(|v| v.parse::<T>())
+        .transpose()
+        .map_err(|message| Error::InvalidArgument { name, message })
+}
+
+impl TaskFilter {
+    pub fn from_matches(matches: &ArgMatches) -> Result<Self> {
+        let name = matches
+            .value_of("name")
+            .map(NamePattern::parse)
+            .transpose()
+            .map_err(|message| Error::InvalidArgument { name: "name", message })?;
+        Ok(TaskFilter {
+            task_type: parse_arg(matches, "type")?,
+            name,
+            frequency: parse_arg(matches, "frequency")?,
+            sort: parse_arg(matches, "sort")?,
+            reverse: matches.is_present("reverse"),
+        })
+    }
+
+    /// Filter from the --name-match and --type selectors of the bulk commands
+    pub fn from_selector_matches(matches: &ArgMatches) -> Result<Self> {
+        let name = matches
+            .value_of("name-match")
+            .map(NamePattern::parse)
+            .transpose()
+            .map_err(|message| Error::InvalidArgument { name: "name-match", message })?;
+        Ok(TaskFilter {
+            task_type: parse_arg(matches, "type")?,
+            name,
+            ..Default::default()
+        })
+    }
+
+    /// Whether the filter narrows the tasks down at all
+    pub fn is_empty(&self) -> bool {
+        self.task_type.is_none() && self.name.is_none() && self.frequency.is_none()
+    }
+
+    pub fn matches(&self, task: &Task) -> bool {
+        if let Some(task_type) = self.task_type {
+            if task.task_type != task_type {
+                return false;
+            }
+        }
+        if let Some(name) = &self.name {
+            if !name.is_match(&task.task_name) {
+                return false;
+            }
+        }
+        match self.frequency {
+            Some(FrequencyKind::Hook) => task.frequency == "Hook",
+            Some(FrequencyKind::Scheduled) => task.frequency != "Hook",
+            None => true,
+        }
+    }
+
+    fn compare(&self, a: &Task, b: &Task) -> Ordering {
+        match self.sort {
+            Some(TaskSortKey::Name) => a.task_name.to_lowercase().cmp(&b.task_name.to_lowercase()),
+            Some(TaskSortKey::Type) => a.task_type.to_string().cmp(&b.task_type.to_string()),
+            Some(TaskSortKey::Frequency) => a.frequency.cmp(&b.frequency),
+            Some(TaskSortKey::Created) => a.created_at.cmp(&b.created_at),
+            None => Ordering::Equal,
+        }
+    }
+
+    /// Drops the tasks that don't match and sorts the rest, keeping server order for ties
+    pub fn apply(&self, tasks: Vec<Task>) -> Vec<Task> {
+        let mut tasks = tasks.into_iter().filter(|t| self.matches(t)).collect::<Vec<_>>();
+        tasks.sort_by(|a, b| self.compare(a, b));
+        if self.reverse {
+            tasks.reverse();
+        }
+        tasks
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ReportFilter {
+    pub successful: Option<bool>,
+    pub since: Option<NaiveDateTime>,
+    pub until: Option<NaiveDateTime>,
+    pub limit: Option<usize>,
+}
+
+/// Global args are only visible in the matches of the subcommand they're written after,
+/// so they're looked up in the parent's matches as well.
+fn value_of<'a>(matches: &[&'a ArgMatches], name: &str) -> Option<&'a str> {
+    matches.iter().find_map(|m| m.value_of(name))
+}
+
+fn is_present(matches: &[&ArgMatches], name: &str) -> bool {
+    matches.iter().any(|m| m.is_present(name))
+}
+
+fn parse_time_arg(matches: &[&ArgMatches], name: &'static str) -> Result<Option<NaiveDateTime>> {
+    value_of(matches, name)
+        .map(parse_time)
+        .transpose()
+        .map_err(|message| Error::InvalidArgument { name, message })
+}
+
+impl ReportFilter {
+    pub fn from_matches(matches: &[&ArgMatches]) -> Result<Self> {
+        let successful = if is_present(matches, "failed") {
+            Some(false)
+        } else if is_present(matches, "succeeded") {
+            Some(true)
+        } else {
+            None
+        };
+        let limit = value_of(matches, "limit")
+            .map(|l| l.parse::<usize>())
+            .transpose()
+            .map_err(|e| Error::InvalidArgument { name: "limit", message: e.to_string() })?;
+        Ok(ReportFilter {
+            successful,
+            since: parse_time_arg(matches, "since")?,
+            until: parse_time_arg(matches, "until")?,
+            limit,
+        })
+    }
+
+    pub fn matches(&self, report: &Report) -> bool {
+        if let Some(successful) = self.successful {
+            if report.successful != successful {
+                return false;
+            }
+        }
+        if let Some(since) = self.since {
+            if report.created_at < since {
+                return false;
+            }
+        }
+        if let Some(until) = self.until {
+            if report.created_at > until {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn apply(&self, reports: Vec<Report>) -> Vec<Report> {
+        let reports = reports.into_iter().filter(|r| self.matches(r));
+        match self.limit {
+            Some(limit) => reports.take(limit).collect(),
+            None => reports.collect(),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! Client library for [StewardX](https://github.com/gokayokyay/stewardx).
+//!
+//! The `stxctl` binary is built on top of this crate, but it can be used on its own:
+//!
+//! ```no_run
+//! use stewardx_cli::StewardxClient;
+//!
+//! let client = StewardxClient::from_env();
+//! for task in client.tasks().unwrap() {
+//!     println!("{} {}", task.id, task.task_name);
+//! }
+//! ```
+
+pub mod client;
+pub mod error;
+pub mod models;
+
+pub use client::{PreparedRequest, StewardxClient, DEFAULT_TIMEOUT};
+pub use error::{Error, Result};
+pub use models::{
+    CmdTaskProps, DockerImage, DockerImageType, DockerTaskProps, NewTask, Report, Task, TaskProps,
+    TaskType, TaskUpdate,
+};
@@ -1,113 +1,292 @@
+/// Like println!, but a closed stdout ends the CLI quietly instead of panicking, see
+/// output::write_stdout
+macro_rules! outln {
+    () => {
+        $crate::output::write_stdout(format_args!("\n"))
+    };
+    ($($arg:tt)*) => {
+        $crate::output::write_stdout(format_args!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+/// Like print!, see outln!
+macro_rules! out {
+    ($($arg:tt)*) => {
+        $crate::output::write_stdout(format_args!($($arg)*))
+    };
+}
+
 mod api;
+mod color;
+mod dashboard;
+mod diff;
+mod edit;
+mod filter;
+mod manifest;
 mod output;
 mod utils;
 mod stewardx;
+mod table;
+mod template;
 
-use std::fs;
 use std::process;
 
 use api::{
-    abort_task, delete_task, execute_task, get_active_tasks, get_latest_reports,
-    get_reports_for_task,
+    abort_task, clone_task, delete_task, execute_task, execute_task_and_wait, follow_reports,
+    get_active_tasks, get_latest_reports, get_reports_for_task, show_report, update_task,
+    run_bulk, watch_active_tasks, Confirmation, TaskChanges, TaskOperation,
 };
 use clap::{load_yaml, App, ArgMatches};
 use env_logger::Env;
 use stewardx::fetch_latest_binary;
+use stewardx_cli::{
+    CmdTaskProps, DockerTaskProps, Error, NewTask, Result,
+    StewardxClient, TaskProps, TaskType,
+};
+
+use crate::{color::set_color_choice, dashboard::show_dashboard, edit::edit_task, manifest::{apply_manifests, diff_manifests, export_tasks, import_tasks, OnExisting}, filter::{ReportFilter, TaskFilter}, api::{create_task, get_report, get_task, get_tasks}, output::{print_error, OutputFormat, OutputOptions}, stewardx::{start_stewardx, stop_stewardx}, utils::{docker_image, parse_docker_type, parse_duration, parse_frequency}};
+
+/// Tasks picked by a command that works on several at once
+enum Selection {
+    /// A single id and nothing else, the command keeps its plain output
+    Single(String),
+    Bulk(Vec<String>, TaskFilter),
+}
+
+/// Full id of the task given as the argument, which can also be a short id or `name:<task name>`
+fn task_id(client: &StewardxClient, matches: &ArgMatches, name: &str) -> Result<Option<String>> {
+    matches.value_of(name).map(|id| client.resolve_task_id(id)).transpose()
+}
+
+fn task_selection(client: &StewardxClient, matches: &ArgMatches) -> Result<Selection> {
+    let ids = matches.values_of("ID").unwrap_or_default().collect::<Vec<&str>>();
+    let filter = TaskFilter::from_selector_matches(matches)?;
+    let mut ids = client.resolve_task_ids(&ids)?;
+    if ids.len() == 1 && filter.is_empty() {
+        Ok(Selection::Single(ids.remove(0)))
+    } else {
+        Ok(Selection::Bulk(ids, filter))
+    }
+}
 
-use crate::{api::{create_task, get_report, get_task, get_tasks}, stewardx::{start_stewardx, stop_stewardx}, utils::{capitalize, parse_cron_frequency}};
+fn confirmation(matches: &ArgMatches) -> Confirmation {
+    Confirmation {
+        yes: matches.is_present("yes"),
+        dry_run: matches.is_present("dry-run"),
+    }
+}
 
-fn handle_tasks(tasks: &ArgMatches) {
+fn handle_tasks(client: &StewardxClient, tasks: &ArgMatches, options: &OutputOptions) -> Result<()> {
     if let Some(list) = tasks.subcommand_matches("list") {
-        if let Some(task_id) = list.value_of("ID") {
-            get_task(task_id);
+        let options = &options.with_quiet(list.is_present("quiet"));
+        if let Some(task_id) = task_id(client, list, "ID")? {
+            get_task(client, &task_id, options)?;
         } else {
-            get_tasks();
+            let filter = TaskFilter::from_matches(list)?;
+            get_tasks(client, &filter, options)?;
         }
     }
     if let Some(create) = tasks.subcommand_matches("create") {
         if let Some(cmd) = create.subcommand_matches("cmd") {
+            let options = &options.with_quiet(cmd.is_present("quiet"));
             let command = cmd.value_of("command").unwrap();
             let name = cmd.value_of("name").unwrap();
-            let frequency = cmd.value_of("frequency").unwrap();
-            let frequency = if frequency == "Hook" {
-                frequency.to_string()
-            } else {
-                parse_cron_frequency(frequency)
+            let frequency = parse_frequency(cmd.value_of("frequency").unwrap())?;
+            let task = NewTask {
+                task_type: TaskType::CmdTask,
+                task_name: name.to_string(),
+                frequency,
+                task_props: TaskProps::Cmd(CmdTaskProps {
+                    command: command.to_string(),
+                }),
             };
-            let props = serde_json::json!({ "command": command });
-            create_task("CmdTask", name, &frequency, &props);
+            create_task(client, &task, options)?;
         } else if let Some(docker) = create.subcommand_matches("docker") {
+            let options = &options.with_quiet(docker.is_present("quiet"));
             let name = docker.value_of("name").unwrap();
-            let frequency = docker.value_of("frequency").unwrap();
-            let frequency = if frequency == "Hook" {
-                frequency.to_string()
-            } else {
-                parse_cron_frequency(frequency)
-            };
-            let docker_type = docker.value_of("type").unwrap();
-            let contents = docker.value_of("contents").unwrap();
+            let frequency = parse_frequency(docker.value_of("frequency").unwrap())?;
+            let docker_type = parse_docker_type(docker.value_of("type").unwrap())?;
+            let image = docker_image(docker_type, docker.value_of("contents").unwrap())?;
             let environment_vars = docker
                 .values_of("env")
-                .unwrap_or(clap::Values::default())
-                .collect::<Vec<&str>>();
-            let contents = match docker_type {
-                "file" => match fs::read_to_string(contents) {
-                    Ok(c) => c,
-                    Err(_e) => {
-                        eprintln!("Couldn't read the file specified, please make sure the Dockerfile's path is correct.");
-                        process::exit(1);
-                    }
-                },
-                "image" => contents.to_string(),
-                _ => {
-                    eprintln!("Invalid type specified, please supply either \"file\" or \"image\"");
-                    process::exit(1);
-                }
+                .unwrap_or_default()
+                .map(|e| e.to_string())
+                .collect::<Vec<String>>();
+            let task = NewTask {
+                task_type: TaskType::DockerTask,
+                task_name: name.to_string(),
+                frequency,
+                task_props: TaskProps::Docker(DockerTaskProps {
+                    image,
+                    env: environment_vars,
+                }),
             };
-            let docker_type = capitalize(docker_type);
-            let task_props = serde_json::json!({
-                "image": {
-                    "t": docker_type,
-                    "c": contents
-                },
-                "env": environment_vars
-            });
-            create_task("DockerTask", name, &frequency, &task_props);
+            create_task(client, &task, options)?;
         } else {
-            eprintln!("Error: please supply either cmd or docker to create command");
-            process::exit(1);
+            return Err(Error::Usage("please supply either cmd or docker to create command".into()));
         }
     }
-    if let Some(_) = tasks.subcommand_matches("active") {
-        get_active_tasks();
+    if let Some(update) = tasks.subcommand_matches("update") {
+        let options = &options.with_quiet(update.is_present("quiet"));
+        let task_id = task_id(client, update, "ID")?.unwrap();
+        let changes = TaskChanges {
+            name: update.value_of("name").map(String::from),
+            frequency: update.value_of("frequency").map(String::from),
+            command: update.value_of("command").map(String::from),
+            docker_type: update.value_of("type").map(parse_docker_type).transpose()?,
+            contents: update.value_of("contents").map(String::from),
+            env: update
+                .values_of("env")
+                .map(|env| env.map(String::from).collect()),
+        };
+        update_task(client, &task_id, &changes, options)?;
+    }
+    if let Some(edit) = tasks.subcommand_matches("edit") {
+        edit_task(client, &task_id(client, edit, "ID")?.unwrap(), options)?;
+    }
+    if let Some(export) = tasks.subcommand_matches("export") {
+        let format = options.format.unwrap_or(OutputFormat::Yaml);
+        export_tasks(client, format, export.value_of("filename"), export.is_present("strip-ids"))?;
+    }
+    if let Some(import) = tasks.subcommand_matches("import") {
+        let options = &options.with_quiet(import.is_present("quiet"));
+        let paths = import.values_of("filename").unwrap().collect::<Vec<&str>>();
+        let on_existing = if import.is_present("skip-existing") {
+            OnExisting::Skip
+        } else if import.is_present("overwrite") {
+            OnExisting::Overwrite
+        } else {
+            OnExisting::Fail
+        };
+        let target = import
+            .value_of("target-url")
+            .map(|url| StewardxClient::new(url).with_timeout(client.timeout()));
+        import_tasks(target.as_ref().unwrap_or(client), &paths, on_existing, options)?;
+    }
+    if let Some(clone) = tasks.subcommand_matches("clone") {
+        let options = &options.with_quiet(clone.is_present("quiet"));
+        let env = clone
+            .values_of("env")
+            .unwrap_or_default()
+            .map(String::from)
+            .collect::<Vec<String>>();
+        clone_task(
+            client,
+            &task_id(client, clone, "ID")?.unwrap(),
+            clone.value_of("name").unwrap(),
+            clone.value_of("frequency"),
+            &env,
+            options,
+        )?;
+    }
+    if let Some(active) = tasks.subcommand_matches("active") {
+        let options = &options.with_quiet(active.is_present("quiet"));
+        if active.is_present("watch") {
+            // No default_value in cli.yaml, clap counts it as given and --interval requires --watch
+            let interval = parse_duration(active.value_of("interval").unwrap_or("2s"))
+                .map_err(|message| Error::InvalidArgument { name: "interval", message })?;
+            watch_active_tasks(client, interval)?;
+        } else {
+            get_active_tasks(client, options)?;
+        }
     }
     if let Some(delete) = tasks.subcommand_matches("delete") {
-        let task_id = delete.value_of("ID").unwrap();
-        delete_task(task_id);
+        let confirmation = confirmation(delete);
+        match task_selection(client, delete)? {
+            Selection::Bulk(ids, filter) => {
+                let options = &options.with_quiet(delete.is_present("quiet"));
+                run_bulk(client, TaskOperation::Delete, &ids, &filter, &confirmation, options)?;
+            }
+            Selection::Single(id) => delete_task(client, &id, &confirmation)?,
+        }
     }
     if let Some(execute) = tasks.subcommand_matches("execute") {
-        let task_id = execute.value_of("ID").unwrap();
-        execute_task(task_id);
+        let selection = task_selection(client, execute)?;
+        if execute.is_present("wait") {
+            let id = match selection {
+                Selection::Single(id) => id,
+                Selection::Bulk(..) => {
+                    return Err(Error::InvalidArgument {
+                        name: "wait",
+                        message: "only a single task can be waited for".into(),
+                    })
+                }
+            };
+            let timeout = execute
+                .value_of("timeout")
+                .map(parse_duration)
+                .transpose()
+                .map_err(|message| Error::InvalidArgument { name: "timeout", message })?;
+            execute_task_and_wait(client, &id, timeout, options)?;
+        } else {
+            match selection {
+                Selection::Bulk(ids, filter) => {
+                    let options = &options.with_quiet(execute.is_present("quiet"));
+                    let confirmation = Confirmation {
+                        yes: true,
+                        dry_run: false,
+                    };
+                    run_bulk(client, TaskOperation::Execute, &ids, &filter, &confirmation, options)?;
+                }
+                Selection::Single(id) => execute_task(client, &id)?,
+            }
+        }
     }
     if let Some(abort) = tasks.subcommand_matches("abort") {
-        let task_id = abort.value_of("ID").unwrap();
-        abort_task(task_id);
+        let confirmation = confirmation(abort);
+        match task_selection(client, abort)? {
+            Selection::Bulk(ids, filter) => {
+                let options = &options.with_quiet(abort.is_present("quiet"));
+                run_bulk(client, TaskOperation::Abort, &ids, &filter, &confirmation, options)?;
+            }
+            Selection::Single(id) => abort_task(client, &id, &confirmation)?,
+        }
     }
+    Ok(())
 }
 
-fn handle_reports(reports: &ArgMatches) {
+fn handle_reports(client: &StewardxClient, reports: &ArgMatches, options: &OutputOptions) -> Result<()> {
+    let options = &options.with_quiet(reports.is_present("quiet"));
     if let Some(list) = reports.subcommand_matches("list") {
+        let options = &options.with_quiet(list.is_present("quiet"));
+        let filter = ReportFilter::from_matches(&[reports, list])?;
         if let Some(report_id) = list.value_of("ID") {
-            get_report(report_id);
+            get_report(client, report_id, options)?;
         } else {
-            get_latest_reports();
+            get_latest_reports(client, &filter, options)?;
         }
     }
-    if let Some(task) = reports.value_of("task") {
-        get_reports_for_task(task);
+    if let Some(follow) = reports.subcommand_matches("follow") {
+        let options = &options.with_quiet(follow.is_present("quiet"));
+        let filter = ReportFilter::from_matches(&[reports, follow])?;
+        let task = match task_id(client, follow, "task")? {
+            Some(task) => Some(task),
+            None => task_id(client, reports, "task")?,
+        };
+        let interval = parse_duration(follow.value_of("interval").unwrap())
+            .map_err(|message| Error::InvalidArgument { name: "interval", message })?;
+        return follow_reports(client, task.as_deref(), interval, &filter, options);
     }
-    if let Some(_latest) = reports.subcommand_matches("latest") {
-        get_latest_reports()
+    if let Some(task) = task_id(client, reports, "task")? {
+        let filter = ReportFilter::from_matches(&[reports])?;
+        get_reports_for_task(client, &task, &filter, options)?;
+    }
+    if let Some(show) = reports.subcommand_matches("show") {
+        let report_id = show.value_of("ID").unwrap();
+        show_report(client, report_id, show.is_present("raw"), options)?;
+    }
+    if let Some(latest) = reports.subcommand_matches("latest") {
+        let options = &options.with_quiet(latest.is_present("quiet"));
+        let filter = ReportFilter::from_matches(&[reports, latest])?;
+        get_latest_reports(client, &filter, options)?;
+    }
+    Ok(())
+}
+
+fn exit_on_error(result: Result<()>) {
+    if let Err(e) = result {
+        print_error(&e);
+        process::exit(e.exit_code());
     }
 }
 
@@ -118,11 +297,40 @@ fn main() {
     let yaml = load_yaml!("cli.yaml");
     let matches = App::from(yaml).get_matches();
     // println!("{:?}", matches);
+    if let Ok(choice) = matches.value_of_t("color") {
+        set_color_choice(choice);
+    }
+    let client = StewardxClient::from_env();
+    let options = OutputOptions {
+        format: matches.value_of_t("output").ok(),
+        columns: matches
+            .values_of("columns")
+            .map(|c| c.map(|c| c.trim().to_string()).collect()),
+        template: matches.value_of("template").map(|t| t.to_string()),
+        ..Default::default()
+    };
     if let Some(tasks) = matches.subcommand_matches("tasks") {
-        handle_tasks(tasks);
+        exit_on_error(handle_tasks(&client, tasks, &options));
     }
     if let Some(reports) = matches.subcommand_matches("reports") {
-        handle_reports(reports);
+        exit_on_error(handle_reports(&client, reports, &options));
+    }
+    if let Some(apply) = matches.subcommand_matches("apply") {
+        let paths = apply.values_of("filename").unwrap().collect::<Vec<&str>>();
+        let confirmation = confirmation(apply);
+        exit_on_error(apply_manifests(&client, &paths, apply.is_present("prune"), &confirmation));
+    }
+    if let Some(diff) = matches.subcommand_matches("diff") {
+        let paths = diff.values_of("filename").unwrap().collect::<Vec<&str>>();
+        match diff_manifests(&client, &paths, diff.is_present("prune")) {
+            Ok(true) => process::exit(1),
+            result => exit_on_error(result.map(|_| ())),
+        }
+    }
+    if let Some(dashboard) = matches.subcommand_matches("dashboard") {
+        let interval = parse_duration(dashboard.value_of("interval").unwrap())
+            .map_err(|message| Error::InvalidArgument { name: "interval", message });
+        exit_on_error(interval.and_then(|interval| show_dashboard(&client, interval)));
     }
     if let Some(_install) = matches.subcommand_matches("install") {
         fetch_latest_binary();
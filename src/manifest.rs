@@ -0,0 +1,463 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use stewardx_cli::{Error, NewTask, Result, StewardxClient, Task, TaskProps, TaskType, TaskUpdate};
+
+use crate::{
+    api::{confirm, Confirmation, TaskOperation},
+    color::{paint, Stream, Style},
+    diff::unified_diff,
+    output::{print_import_results, to_yaml, OutputFormat, OutputOptions},
+    utils::{parse_frequency, validate_task},
+};
+
+/// A task as it's written in a manifest. The field names StewardX uses, like task_name, work too.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TaskManifest {
+    /// Id the task had on the server it was exported from, tasks are matched by name regardless
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(alias = "task_name")]
+    pub name: String,
+    #[serde(rename = "type", alias = "task_type")]
+    pub task_type: TaskType,
+    pub frequency: String,
+    #[serde(alias = "task_props")]
+    pub props: TaskProps,
+}
+
+impl From<TaskManifest> for NewTask {
+    fn from(manifest: TaskManifest) -> Self {
+        NewTask {
+            task_type: manifest.task_type,
+            task_name: manifest.name,
+            frequency: manifest.frequency,
+            task_props: manifest.props,
+        }
+    }
+}
+
+impl From<NewTask> for TaskManifest {
+    fn from(task: NewTask) -> Self {
+        TaskManifest {
+            id: None,
+            name: task.task_name,
+            task_type: task.task_type,
+            frequency: task.frequency,
+            props: task.task_props,
+        }
+    }
+}
+
+fn invalid_manifest(path: &Path, message: impl std::fmt::Display) -> Error {
+    Error::InvalidTaskField {
+        field: "manifest",
+        message: format!("{}: {}", path.display(), message),
+    }
+}
+
+/// Manifest files in a directory, in name order so applying it is repeatable
+fn manifest_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = fs::read_dir(dir).map_err(|e| Error::InvalidArgument {
+        name: "filename",
+        message: format!("couldn't read {}: {}", dir.display(), e),
+    })?;
+    let mut files = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+            path.is_file() && ["yaml", "yml", "json"].contains(&extension)
+        })
+        .collect::<Vec<PathBuf>>();
+    files.sort();
+    Ok(files)
+}
+
+/// Reads every document in the file. A document is either a single task or a list of tasks.
+fn load_file(path: &Path) -> Result<Vec<TaskManifest>> {
+    let contents = fs::read_to_string(path).map_err(|e| Error::InvalidArgument {
+        name: "filename",
+        message: format!("couldn't read {}: {}", path.display(), e),
+    })?;
+    let mut tasks = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(&contents) {
+        let value = Value::deserialize(document).map_err(|e| invalid_manifest(path, e))?;
+        let values = match value {
+            Value::Null => continue,
+            Value::Sequence(values) => values,
+            value => vec![value],
+        };
+        for value in values {
+            let manifest =
+                serde_yaml::from_value::<TaskManifest>(value).map_err(|e| invalid_manifest(path, e))?;
+            let (id, name) = (manifest.id.clone(), manifest.name.clone());
+            let task = validate_task(manifest.into())
+                .map_err(|e| invalid_manifest(path, format!("{}: {}", name, e)))?;
+            tasks.push(TaskManifest { id, ..task.into() });
+        }
+    }
+    Ok(tasks)
+}
+
+/// Loads the tasks from manifest files or directories of them, names have to be unique
+pub fn load_manifests(paths: &[&str]) -> Result<Vec<TaskManifest>> {
+    let mut tasks = Vec::new();
+    for path in paths {
+        let path = Path::new(path);
+        let files = if path.is_dir() {
+            manifest_files(path)?
+        } else {
+            vec![path.to_path_buf()]
+        };
+        for file in files {
+            tasks.extend(load_file(&file)?);
+        }
+    }
+    let mut names = HashSet::new();
+    for task in &tasks {
+        if !names.insert(task.name.as_str()) {
+            return Err(Error::InvalidTaskField {
+                field: "manifest",
+                message: format!("task \"{}\" is defined more than once", task.name),
+            });
+        }
+    }
+    Ok(tasks)
+}
+
+/// What has to be done to a task to make the server match the manifests
+#[derive(Clone, Debug)]
+pub enum Change {
+    Create(NewTask),
+    Update { task: Box<Task>, desired: NewTask },
+    Unchanged(Task),
+    Delete(Task),
+}
+
+/// The task as a manifest would describe it, with its frequency written the way
+/// `parse_frequency` writes it so the two can be compared
+fn normalize(task: &Task) -> NewTask {
+    let mut task = NewTask::from(task);
+    if let Ok(frequency) = parse_frequency(&task.frequency) {
+        task.frequency = frequency;
+    }
+    task
+}
+
+/// Matches the manifests to the tasks on the server by name. Tasks that aren't in the manifests
+/// are deleted only when pruning. A changed type is planned as an update, `apply` refuses it.
+pub fn plan(manifests: Vec<NewTask>, tasks: &[Task], prune: bool) -> Result<Vec<Change>> {
+    let mut changes = Vec::new();
+    for desired in manifests {
+        let matching = tasks
+            .iter()
+            .filter(|t| t.task_name == desired.task_name)
+            .collect::<Vec<&Task>>();
+        let change = match matching.as_slice() {
+            [] => Change::Create(desired),
+            [task] if normalize(task) == desired => Change::Unchanged((*task).clone()),
+            [task] => Change::Update {
+                task: Box::new((*task).clone()),
+                desired,
+            },
+            _ => {
+                return Err(Error::InvalidTaskField {
+                    field: "name",
+                    message: format!(
+                        "there are {} tasks named \"{}\" on the server, names have to be unique to be applied",
+                        matching.len(),
+                        desired.task_name
+                    ),
+                });
+            }
+        };
+        changes.push(change);
+    }
+    if prune {
+        let names = changes
+            .iter()
+            .map(|change| match change {
+                Change::Create(desired) | Change::Update { desired, .. } => desired.task_name.clone(),
+                Change::Unchanged(task) | Change::Delete(task) => task.task_name.clone(),
+            })
+            .collect::<HashSet<String>>();
+        let pruned = tasks.iter().filter(|t| !names.contains(&t.task_name));
+        changes.extend(pruned.cloned().map(Change::Delete));
+    }
+    Ok(changes)
+}
+
+/// The type of a task can't be updated, so applying stops before sending anything if a manifest
+/// changes one
+fn check_types(changes: &[Change]) -> Result<()> {
+    for change in changes {
+        if let Change::Update { task, desired } = change {
+            if task.task_type != desired.task_type {
+                return Err(Error::InvalidTaskField {
+                    field: "type",
+                    message: format!(
+                        "task \"{}\" is a {} on the server, delete it first to change its type",
+                        task.task_name, task.task_type
+                    ),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Prints the requests applying the changes would send, without sending them
+fn print_requests(client: &StewardxClient, changes: &[Change]) -> Result<()> {
+    let mut requests = Vec::new();
+    for change in changes {
+        let request = match change {
+            Change::Create(desired) => client.create_task_request(desired)?,
+            Change::Update { task, desired } => {
+                client.update_task_request(&task.id, &TaskUpdate::between(task, desired))?
+            }
+            Change::Delete(task) => TaskOperation::Delete.request(client, &task.id),
+            Change::Unchanged(_) => continue,
+        };
+        requests.push(request.to_string());
+    }
+    outln!("{}", requests.join("\n\n"));
+    Ok(())
+}
+
+/// Creates, updates and, if pruning, deletes tasks until the server matches the manifests
+pub fn apply_manifests(
+    client: &StewardxClient,
+    paths: &[&str],
+    prune: bool,
+    confirmation: &Confirmation,
+) -> Result<()> {
+    let manifests = load_manifests(paths)?.into_iter().map(NewTask::from).collect();
+    let changes = plan(manifests, &client.tasks()?, prune)?;
+    check_types(&changes)?;
+    if confirmation.dry_run {
+        return print_requests(client, &changes);
+    }
+    let pruned = changes
+        .iter()
+        .filter_map(|change| match change {
+            Change::Delete(task) => Some((task.id.clone(), Some(task.clone()))),
+            _ => None,
+        })
+        .collect::<Vec<(String, Option<Task>)>>();
+    // Pruning is asked for before anything is sent, so declining leaves StewardX as it was
+    if !pruned.is_empty() && !confirmation.yes && !confirm(TaskOperation::Delete, &pruned)? {
+        eprintln!("Cancelled, nothing was sent");
+        return Ok(());
+    }
+    for change in changes {
+        match change {
+            Change::Create(desired) => {
+                let task = client.create_task(&desired)?;
+                let status = paint("created", Style::Green, Stream::Stdout);
+                outln!("task \"{}\" {} ({})", task.task_name, status, task.id);
+            }
+            Change::Update { task, desired } => {
+                client.update_task(&task.id, &TaskUpdate::between(&task, &desired))?;
+                outln!("task \"{}\" updated", task.task_name);
+            }
+            Change::Unchanged(task) => {
+                let status = paint("unchanged", Style::Dim, Stream::Stdout);
+                outln!("task \"{}\" {}", task.task_name, status);
+            }
+            Change::Delete(task) => {
+                client.delete_task(&task.id)?;
+                let status = paint("pruned", Style::Red, Stream::Stdout);
+                outln!("task \"{}\" {} ({})", task.task_name, status, task.id);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn manifest_yaml(task: NewTask) -> Result<String> {
+    let yaml = to_yaml(&TaskManifest::from(task))?;
+    // The document start marker would show up in every diff without telling anything
+    Ok(yaml.trim_start_matches("---\n").to_string())
+}
+
+/// Prints what applying the manifests would change as a unified diff between the tasks on the
+/// server and the manifests. Returns whether there's any difference.
+pub fn diff_manifests(client: &StewardxClient, paths: &[&str], prune: bool) -> Result<bool> {
+    let manifests = load_manifests(paths)?.into_iter().map(NewTask::from).collect();
+    let changes = plan(manifests, &client.tasks()?, prune)?;
+    let mut drifted = false;
+    for change in changes {
+        let (live, desired, name) = match change {
+            Change::Create(desired) => {
+                let name = desired.task_name.clone();
+                (String::new(), manifest_yaml(desired)?, name)
+            }
+            Change::Update { task, desired } => {
+                (manifest_yaml(normalize(&task))?, manifest_yaml(desired)?, task.task_name)
+            }
+            Change::Delete(task) => (manifest_yaml(normalize(&task))?, String::new(), task.task_name),
+            Change::Unchanged(_) => continue,
+        };
+        let label = |source: &str, text: &str| {
+            if text.is_empty() {
+                "/dev/null".to_string()
+            } else {
+                format!("{}/{}", source, name)
+            }
+        };
+        outln!(
+            "{}",
+            unified_diff(&live, &desired, &label("live", &live), &label("manifest", &desired))
+        );
+        drifted = true;
+    }
+    Ok(drifted)
+}
+
+/// Writes every task as a list of manifests that `apply` and `tasks import` can read back,
+/// either to stdout or to the given file
+pub fn export_tasks(
+    client: &StewardxClient,
+    format: OutputFormat,
+    file: Option<&str>,
+    strip_ids: bool,
+) -> Result<()> {
+    let tasks = client.tasks()?;
+    let manifests = tasks
+        .iter()
+        .map(|task| TaskManifest {
+            id: Some(task.id.clone()).filter(|_| !strip_ids),
+            ..TaskManifest::from(NewTask::from(task))
+        })
+        .collect::<Vec<TaskManifest>>();
+    let document = match format {
+        OutputFormat::Json => serde_json::to_string_pretty(&manifests)? + "\n",
+        OutputFormat::Yaml => to_yaml(&manifests)?,
+        _ => {
+            return Err(Error::InvalidArgument {
+                name: "output",
+                message: "tasks can only be exported as yaml or json".into(),
+            })
+        }
+    };
+    match file {
+        Some(file) => {
+            fs::write(file, document).map_err(|e| Error::InvalidArgument {
+                name: "filename",
+                message: format!("couldn't write {}: {}", file, e),
+            })?;
+            eprintln!("Exported {} tasks to {}", manifests.len(), file);
+        }
+        None => out!("{}", document),
+    }
+    Ok(())
+}
+
+/// What `tasks import` does with a task whose name is already taken on the target
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnExisting {
+    Fail,
+    Skip,
+    Overwrite,
+}
+
+/// Outcome of importing a single task
+#[derive(Serialize, Clone, Debug)]
+pub struct ImportResult {
+    pub name: String,
+    pub old_id: Option<String>,
+    pub new_id: Option<String>,
+    /// One of created, updated, unchanged, skipped or failed
+    pub result: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+fn import_task(
+    client: &StewardxClient,
+    desired: &NewTask,
+    existing: Option<&Task>,
+    on_existing: OnExisting,
+) -> Result<(String, &'static str)> {
+    let task = match existing {
+        Some(task) => task,
+        None => return client.create_task(desired).map(|task| (task.id, "created")),
+    };
+    match on_existing {
+        OnExisting::Skip => Ok((task.id.clone(), "skipped")),
+        OnExisting::Fail => Err(Error::InvalidTaskField {
+            field: "name",
+            message: format!(
+                "a task named \"{}\" already exists ({}), pass --skip-existing or --overwrite",
+                task.task_name, task.id
+            ),
+        }),
+        OnExisting::Overwrite if task.task_type != desired.task_type => Err(Error::InvalidTaskField {
+            field: "type",
+            message: format!("task \"{}\" is a {} on the target", task.task_name, task.task_type),
+        }),
+        OnExisting::Overwrite if normalize(task) == *desired => Ok((task.id.clone(), "unchanged")),
+        OnExisting::Overwrite => client
+            .update_task(&task.id, &TaskUpdate::between(task, desired))
+            .map(|task| (task.id, "updated")),
+    }
+}
+
+/// Creates the tasks of an export on the client's server, one by one so a failing task doesn't
+/// stop the rest. Prints how each went along with the ids they got on the target.
+pub fn import_tasks(
+    client: &StewardxClient,
+    paths: &[&str],
+    on_existing: OnExisting,
+    options: &OutputOptions,
+) -> Result<()> {
+    let manifests = load_manifests(paths)?;
+    let existing = client.tasks()?;
+    let mut first_error = None;
+    let mut results = Vec::new();
+    for manifest in manifests {
+        let old_id = manifest.id.clone();
+        let desired = NewTask::from(manifest);
+        let matching = existing.iter().find(|t| t.task_name == desired.task_name);
+        let result = match import_task(client, &desired, matching, on_existing) {
+            Ok((new_id, result)) => ImportResult {
+                name: desired.task_name,
+                old_id,
+                new_id: Some(new_id),
+                result,
+                error: None,
+            },
+            Err(e) => {
+                let error = e.to_string();
+                first_error.get_or_insert(e);
+                ImportResult {
+                    name: desired.task_name,
+                    old_id,
+                    new_id: None,
+                    result: "failed",
+                    error: Some(error),
+                }
+            }
+        };
+        results.push(result);
+    }
+    print_import_results(&results, options)?;
+    let count = |result| results.iter().filter(|r| r.result == result).count();
+    eprintln!(
+        "{} created, {} updated, {} unchanged, {} skipped, {} failed",
+        count("created"),
+        count("updated"),
+        count("unchanged"),
+        count("skipped"),
+        count("failed")
+    );
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,155 @@
+use std::{fmt, str::FromStr};
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskType {
+    CmdTask,
+    DockerTask,
+}
+
+impl fmt::Display for TaskType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskType::CmdTask => write!(f, "CmdTask"),
+            TaskType::DockerTask => write!(f, "DockerTask"),
+        }
+    }
+}
+
+impl FromStr for TaskType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "CmdTask" => Ok(TaskType::CmdTask),
+            "DockerTask" => Ok(TaskType::DockerTask),
+            _ => Err(format!("unknown task type: {}", s)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CmdTaskProps {
+    pub command: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DockerImageType {
+    File,
+    Image,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DockerImage {
+    /// Whether `c` is a Dockerfile's contents or an image name
+    pub t: DockerImageType,
+    pub c: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DockerTaskProps {
+    pub image: DockerImage,
+    #[serde(default)]
+    pub env: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum TaskProps {
+    Cmd(CmdTaskProps),
+    Docker(DockerTaskProps),
+    /// Props this version of the client doesn't know about, kept as is
+    Other(Value),
+}
+
+// StewardX stores task props as a JSON encoded string, so they may come back either way.
+fn deserialize_task_props<'de, D>(deserializer: D) -> Result<TaskProps, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Value::deserialize(deserializer)?;
+    let value = match value {
+        Value::String(s) => serde_json::from_str(&s).map_err(serde::de::Error::custom)?,
+        v => v,
+    };
+    serde_json::from_value(value).map_err(serde::de::Error::custom)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Task {
+    pub id: String,
+    pub task_name: String,
+    pub task_type: TaskType,
+    pub frequency: String,
+    #[serde(deserialize_with = "deserialize_task_props")]
+    pub task_props: TaskProps,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exec_count: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_execution: Option<NaiveDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_execution: Option<NaiveDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<NaiveDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+/// Body of a task creation request
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct NewTask {
+    pub task_type: TaskType,
+    pub task_name: String,
+    pub frequency: String,
+    pub task_props: TaskProps,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Report {
+    pub id: String,
+    pub task_id: String,
+    pub created_at: NaiveDateTime,
+    pub successful: bool,
+    #[serde(default)]
+    pub output: String,
+}
+
+impl From<&Task> for NewTask {
+    fn from(task: &Task) -> Self {
+        NewTask {
+            task_type: task.task_type,
+            task_name: task.task_name.clone(),
+            frequency: task.frequency.clone(),
+            task_props: task.task_props.clone(),
+        }
+    }
+}
+
+/// Body of a task update request, fields that are left out aren't changed
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct TaskUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_props: Option<TaskProps>,
+}
+
+impl TaskUpdate {
+    /// Builds an update with only the fields where the desired task differs from the current one
+    pub fn between(task: &Task, desired: &NewTask) -> Self {
+        TaskUpdate {
+            task_name: Some(desired.task_name.clone()).filter(|name| name != &task.task_name),
+            frequency: Some(desired.frequency.clone()).filter(|frequency| frequency != &task.frequency),
+            task_props: Some(desired.task_props.clone()).filter(|props| props != &task.task_props),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.task_name.is_none() && self.frequency.is_none() && self.task_props.is_none()
+    }
+}
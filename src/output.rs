@@ -1,10 +1,80 @@
-use std::{process, str::FromStr};
+use std::{
+    fmt,
+    io::{self, Write},
+    process,
+    str::FromStr,
+};
 
+use serde::Serialize;
 use serde_json::Value;
+use stewardx_cli::{DockerImageType, Error, Report, Result, Task, TaskProps};
 
-use crate::utils::{format_date, truncate_string_elliptic};
+use crate::{
+    api::BulkResult,
+    color::{paint, Stream, Style},
+    manifest::ImportResult,
+    table::{print_table, Table},
+    template::{check_template, lookup_path, render_template, render_value},
+    utils::{format_date, format_duration},
+};
 
-pub fn print_connection_failure(e: isahc::Error) {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Table,
+    Wide,
+    Json,
+    Yaml,
+    Csv,
+    Tsv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(OutputFormat::Table),
+            "wide" => Ok(OutputFormat::Wide),
+            "json" => Ok(OutputFormat::Json),
+            "yaml" => Ok(OutputFormat::Yaml),
+            "csv" => Ok(OutputFormat::Csv),
+            "tsv" => Ok(OutputFormat::Tsv),
+            _ => Err(format!("unknown output format: {}", s)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct OutputOptions {
+    /// Format chosen by the user, views fall back to their own default when it's not set
+    pub format: Option<OutputFormat>,
+    /// Print only ids, one per line
+    pub quiet: bool,
+    /// Dotted paths into the JSON representation, replacing the default columns
+    pub columns: Option<Vec<String>>,
+    /// Line printed for each item, with {{path}} placeholders
+    pub template: Option<String>,
+}
+
+impl OutputOptions {
+    fn with_format(&self, format: OutputFormat) -> Self {
+        let mut options = self.clone();
+        options.format = Some(format);
+        options
+    }
+
+    pub fn is_custom(&self) -> bool {
+        self.columns.is_some() || self.template.is_some()
+    }
+
+    pub fn with_quiet(&self, quiet: bool) -> Self {
+        let mut options = self.clone();
+        options.quiet = options.quiet || quiet;
+        options
+    }
+}
+
+pub fn print_connection_failure(e: &isahc::Error) {
     log::debug!("{}", e);
     eprintln!(
         r#"
@@ -13,7 +83,7 @@ pub fn print_connection_failure(e: isahc::Error) {
     );
 }
 
-pub fn print_stewardx_connection_failure(e: isahc::Error) {
+pub fn print_stewardx_connection_failure(e: &isahc::Error) {
     log::debug!("{}", e);
     eprintln!(
         r#"
@@ -26,122 +96,624 @@ pub fn print_stewardx_connection_failure(e: isahc::Error) {
     );
 }
 
-pub fn print_json_failure(e: serde_json::Error) {
+pub fn print_json_failure(e: &serde_json::Error) {
     log::error!("Error while trying to parse response from StewardX, this shouldn't happen, please open an issue at https://github.com/gokayokyay/stewardx-cli");
     log::error!("{}", e);
 }
 
-fn format_and_print_report(id: &str, task_id: &str, executed_at: &str, successful: impl ToString) {
-    println!(
-        "{0: <36} | {1: <36} | {2: <24} | {3: <10}",
-        id,
-        task_id,
-        executed_at,
-        successful.to_string()
-    );
+pub fn print_error(e: &Error) {
+    match e {
+        Error::Connection(e) => print_stewardx_connection_failure(e),
+        Error::Timeout(e) => {
+            eprintln!("StewardX didn't respond in time.");
+            print_stewardx_connection_failure(e);
+        }
+        Error::InvalidJson(e) => print_json_failure(e),
+        e => eprintln!("{} {}", paint("Error:", Style::Red, Stream::Stderr), e),
+    }
 }
 
-pub fn pretty_print_reports(reports: Vec<Value>) {
-    format_and_print_report("Report ID", "Task ID", "Executed At", "Did success");
-    println!("---------------------------------------------------------------------------------------------------------------------");
-    for report in reports {
-        parse_and_print_report(report);
+/// Turns a failed write to stdout into an error. A broken pipe means whoever reads the output,
+/// like `head`, has seen enough, so the CLI exits quietly instead.
+fn stdout_error(e: io::Error) -> Error {
+    if e.kind() == io::ErrorKind::BrokenPipe {
+        process::exit(0);
+    }
+    Error::Io {
+        context: "couldn't write to stdout".into(),
+        source: e,
     }
 }
 
-pub fn print_invalid_report_value(id: &str, key: &str, value: &Value) {
-    log::error!(
-        "Report with id: {} has an invalid property named: \"{}\" with value: \"{}\"",
-        id,
-        key,
-        value
-    );
-    log::error!("Please open an issue at https://github.com/gokayokyay/stewardx-cli and describe how to reproduce it, thanks!");
+/// Writes to a locked stdout, what outln! and out! print with. Errors other than a broken pipe
+/// end the CLI the same way a failed command does.
+pub fn write_stdout(args: fmt::Arguments) {
+    let mut stdout = io::stdout().lock();
+    if let Err(e) = stdout.write_fmt(args).and_then(|_| stdout.flush()) {
+        let e = stdout_error(e);
+        print_error(&e);
+        process::exit(e.exit_code());
+    }
+}
+
+fn csv_error(e: csv::Error) -> Error {
+    stdout_error(io::Error::from(e))
+}
+
+pub fn to_yaml(value: &impl Serialize) -> Result<String> {
+    serde_yaml::to_string(value).map_err(|e| Error::Io {
+        context: "couldn't write YAML".into(),
+        source: io::Error::other(e),
+    })
+}
+
+fn print_delimited(headers: &[&str], rows: &[Vec<String>], delimiter: u8) -> Result<()> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(io::stdout());
+    writer.write_record(headers).map_err(csv_error)?;
+    for row in rows {
+        writer.write_record(row).map_err(csv_error)?;
+    }
+    writer.flush().map_err(stdout_error)
+}
+
+/// Dims ids and highlights whether a report succeeded
+fn style_cell(header: &str, cell: &str) -> Option<Style> {
+    match header {
+        "Did success" | "successful" => match cell {
+            "true" => Some(Style::Green),
+            "false" => Some(Style::Red),
+            _ => None,
+        },
+        "Task ID" | "Report ID" | "id" | "task_id" => Some(Style::Dim),
+        _ => None,
+    }
+}
+
+/// Keeps only the given columns of a serialized item
+fn project(value: &Value, columns: &[String]) -> Value {
+    columns
+        .iter()
+        .map(|c| (c.clone(), lookup_path(value, c).cloned().unwrap_or(Value::Null)))
+        .collect::<serde_json::Map<String, Value>>()
+        .into()
+}
+
+fn custom_row(value: &Value, columns: &[String]) -> Vec<String> {
+    columns.iter().map(|c| render_value(lookup_path(value, c))).collect()
 }
 
-pub fn parse_and_print_report(report: Value) {
-    // If id doesn't exist, then yeah, you can panic
-    let id = &report["id"].as_str().unwrap().to_string();
-    let task_id = match &report["task_id"] {
-        Value::String(v) => v.to_string(),
-        _ => {
-            print_invalid_report_value(id, "task_id", &report["task_id"]);
-            process::exit(1);
+/// Prints the items with user supplied columns or template, whose top level fields have to be
+/// among the item's fields
+fn print_custom<T: Serialize>(items: &[T], fields: &[&str], options: &OutputOptions) -> Result<()> {
+    if let Some(template) = &options.template {
+        check_template(template, fields)?;
+    }
+    let values = items
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<serde_json::Result<Vec<Value>>>()?;
+    if let Some(template) = &options.template {
+        for value in &values {
+            outln!("{}", render_template(template, value));
         }
-    };
-    let created_at = match &report["created_at"] {
-        Value::String(v) => match chrono::NaiveDateTime::from_str(v) {
-            Ok(o) => format_date(o),
-            Err(e) => {
-                log::error!("{}", e);
-                print_invalid_report_value(id, "created_at", &report["created_at"]);
-                process::exit(1);
+        return Ok(());
+    }
+    let columns = options.columns.clone().unwrap_or_default();
+    let headers = columns.iter().map(|c| c.as_str()).collect::<Vec<&str>>();
+    match options.format.unwrap_or(OutputFormat::Table) {
+        format @ OutputFormat::Json | format @ OutputFormat::Yaml => {
+            let projected = values.iter().map(|v| project(v, &columns)).collect::<Vec<_>>();
+            print_serialized(&projected, format)?;
+        }
+        format => {
+            let rows = values.iter().map(|v| custom_row(v, &columns)).collect::<Vec<_>>();
+            match format {
+                OutputFormat::Csv => print_delimited(&headers, &rows, b',')?,
+                OutputFormat::Tsv => print_delimited(&headers, &rows, b'\t')?,
+                _ => print_table(&headers, &rows, style_cell),
             }
+        }
+    }
+    Ok(())
+}
+
+fn print_serialized(value: &impl Serialize, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Yaml => out!("{}", to_yaml(value)?),
+        _ => outln!("{}", serde_json::to_string_pretty(value)?),
+    }
+    Ok(())
+}
+
+/// Prints the value as a single JSON line or a YAML document
+fn print_line(value: &impl Serialize, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Yaml => out!("{}", to_yaml(value)?),
+        _ => outln!("{}", serde_json::to_string(value)?),
+    }
+    Ok(())
+}
+
+fn format_optional_date(date: Option<chrono::NaiveDateTime>) -> String {
+    date.map(format_date).unwrap_or_default()
+}
+
+pub fn format_task_props(props: &TaskProps) -> String {
+    match props {
+        TaskProps::Cmd(cmd) => cmd.command.clone(),
+        TaskProps::Docker(docker) => match docker.image.t {
+            DockerImageType::Image => docker.image.c.clone(),
+            DockerImageType::File => "Dockerfile".to_string(),
         },
-        _ => {
-            print_invalid_report_value(id, "created_at", &report["created_at"]);
-            process::exit(1);
+        TaskProps::Other(value) => value.to_string(),
+    }
+}
+
+const REPORT_HEADERS: [&str; 4] = ["Report ID", "Task ID", "Executed At", "Did success"];
+const WIDE_REPORT_HEADERS: [&str; 5] = ["Report ID", "Task ID", "Executed At", "Did success", "Output"];
+const REPORT_FIELDS: [&str; 5] = ["id", "task_id", "created_at", "successful", "output"];
+
+fn report_row(report: &Report) -> Vec<String> {
+    vec![
+        report.id.clone(),
+        report.task_id.clone(),
+        format_date(report.created_at),
+        report.successful.to_string(),
+    ]
+}
+
+fn wide_report_row(report: &Report) -> Vec<String> {
+    let mut row = report_row(report);
+    row.push(report.output.lines().next().unwrap_or_default().to_string());
+    row
+}
+
+fn full_report_row(report: &Report) -> Vec<String> {
+    let mut row = report_row(report);
+    row.push(report.output.clone());
+    row
+}
+
+pub fn print_reports(reports: &[Report], options: &OutputOptions) -> Result<()> {
+    if options.quiet {
+        reports.iter().for_each(|r| outln!("{}", r.id));
+        return Ok(());
+    }
+    if options.is_custom() {
+        return print_custom(reports, &REPORT_FIELDS, options);
+    }
+    match options.format.unwrap_or(OutputFormat::Table) {
+        OutputFormat::Table => {
+            let rows = reports.iter().map(report_row).collect::<Vec<_>>();
+            print_table(&REPORT_HEADERS, &rows, style_cell);
         }
-    };
-    let successful = match &report["successful"] {
-        Value::Bool(v) => v,
-        _ => {
-            print_invalid_report_value(id, "successful", &report["successful"]);
-            process::exit(1);
+        OutputFormat::Wide => {
+            let rows = reports.iter().map(wide_report_row).collect::<Vec<_>>();
+            print_table(&WIDE_REPORT_HEADERS, &rows, style_cell);
+        }
+        OutputFormat::Csv => {
+            let rows = reports.iter().map(full_report_row).collect::<Vec<_>>();
+            print_delimited(&REPORT_FIELDS, &rows, b',')?;
+        }
+        OutputFormat::Tsv => {
+            let rows = reports.iter().map(full_report_row).collect::<Vec<_>>();
+            print_delimited(&REPORT_FIELDS, &rows, b'\t')?;
+        }
+        format => print_serialized(&reports, format)?,
+    }
+    Ok(())
+}
+
+/// Prints reports as they come in, one line or row per report.
+///
+/// JSON is written as one object per line and YAML as one document per report. Table
+/// columns are sized by the first batch and csv headers are written only once.
+pub struct ReportStream {
+    options: OutputOptions,
+    table: Option<Table>,
+    writer: Option<csv::Writer<std::io::Stdout>>,
+}
+
+impl ReportStream {
+    pub fn new(options: &OutputOptions) -> Self {
+        ReportStream {
+            options: options.clone(),
+            table: None,
+            writer: None,
+        }
+    }
+
+    fn rows(&self, reports: &[Report]) -> Result<(Vec<String>, Vec<Vec<String>>)> {
+        let to_strings = |headers: &[&str]| headers.iter().map(|h| h.to_string()).collect();
+        if let Some(columns) = &self.options.columns {
+            let rows = reports
+                .iter()
+                .map(|r| serde_json::to_value(r).map(|v| custom_row(&v, columns)))
+                .collect::<serde_json::Result<_>>()?;
+            return Ok((columns.clone(), rows));
+        }
+        Ok(match self.options.format.unwrap_or(OutputFormat::Table) {
+            OutputFormat::Table => (to_strings(&REPORT_HEADERS), reports.iter().map(report_row).collect()),
+            OutputFormat::Wide => (
+                to_strings(&WIDE_REPORT_HEADERS),
+                reports.iter().map(wide_report_row).collect(),
+            ),
+            _ => (to_strings(&REPORT_FIELDS), reports.iter().map(full_report_row).collect()),
+        })
+    }
+
+    pub fn print(&mut self, reports: &[Report]) -> Result<()> {
+        if self.options.quiet {
+            reports.iter().for_each(|r| outln!("{}", r.id));
+            return Ok(());
         }
+        if let Some(template) = &self.options.template {
+            check_template(template, &REPORT_FIELDS)?;
+            for report in reports {
+                outln!("{}", render_template(template, &serde_json::to_value(report)?));
+            }
+            return Ok(());
+        }
+        match self.options.format.unwrap_or(OutputFormat::Table) {
+            format @ OutputFormat::Json | format @ OutputFormat::Yaml => {
+                for report in reports {
+                    match &self.options.columns {
+                        Some(columns) => {
+                            print_line(&project(&serde_json::to_value(report)?, columns), format)?
+                        }
+                        None => print_line(report, format)?,
+                    }
+                }
+            }
+            format @ OutputFormat::Csv | format @ OutputFormat::Tsv => {
+                let (headers, rows) = self.rows(reports)?;
+                let writer = match &mut self.writer {
+                    Some(writer) => writer,
+                    None => {
+                        let delimiter = if format == OutputFormat::Csv { b',' } else { b'\t' };
+                        let mut writer = csv::WriterBuilder::new()
+                            .delimiter(delimiter)
+                            .from_writer(io::stdout());
+                        writer.write_record(&headers).map_err(csv_error)?;
+                        self.writer.insert(writer)
+                    }
+                };
+                for row in rows {
+                    writer.write_record(row).map_err(csv_error)?;
+                }
+                writer.flush().map_err(stdout_error)?;
+            }
+            _ => {
+                let (headers, rows) = self.rows(reports)?;
+                // The columns are sized from the first rows, an empty batch would size them to the headers
+                if rows.is_empty() && self.table.is_none() {
+                    return Ok(());
+                }
+                let table = self.table.get_or_insert_with(|| {
+                    let headers = headers.iter().map(|h| h.as_str()).collect::<Vec<&str>>();
+                    let table = Table::new(&headers, &rows, style_cell);
+                    table.print_header();
+                    table
+                });
+                rows.iter().for_each(|row| table.print_row(row));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Single report views print JSON unless told otherwise
+pub fn print_report(report: &Report, options: &OutputOptions) -> Result<()> {
+    if options.quiet {
+        outln!("{}", report.id);
+        return Ok(());
+    }
+    if options.is_custom() {
+        return print_custom(std::slice::from_ref(report), &REPORT_FIELDS, options);
+    }
+    match options.format.unwrap_or(OutputFormat::Json) {
+        format @ OutputFormat::Json | format @ OutputFormat::Yaml => print_serialized(report, format),
+        format => print_reports(std::slice::from_ref(report), &options.with_format(format)),
+    }
+}
+
+/// Summary of a report followed by its output.
+///
+/// StewardX doesn't store how long a run took, but the task's last execution marks the
+/// start of its latest run, so the duration is known for the latest report of a task.
+pub fn print_report_details(report: &Report, task: Option<&Task>, options: &OutputOptions) -> Result<()> {
+    if options.format.is_some() || options.quiet || options.is_custom() {
+        return print_report(report, options);
+    }
+    let task_name = match task {
+        Some(task) => format!("{} ({})", task.task_name, report.task_id),
+        None => report.task_id.clone(),
     };
-    format_and_print_report(id, &task_id, &created_at, *successful);
+    let duration = task
+        .and_then(|t| t.last_execution)
+        .filter(|started_at| *started_at <= report.created_at)
+        .map(|started_at| format_duration(report.created_at - started_at));
+    let status = if report.successful {
+        paint("succeeded", Style::Green, Stream::Stdout)
+    } else {
+        paint("failed", Style::Red, Stream::Stdout)
+    };
+    let label = |l: &str| paint(&format!("{: <12}", l), Style::Bold, Stream::Stdout);
+    outln!("{}{}", label("Report"), report.id);
+    outln!("{}{}", label("Task"), task_name);
+    outln!("{}{}", label("Executed At"), format_date(report.created_at));
+    if let Some(duration) = duration {
+        outln!("{}{}", label("Duration"), duration);
+    }
+    outln!("{}{}", label("Status"), status);
+    outln!();
+    print_report_output(report);
+    if !report.output.is_empty() && !report.output.ends_with('\n') {
+        outln!();
+    }
+    Ok(())
 }
 
-fn format_and_print_task(id: &str, name: &str, task_type: &str, frequency: &str) {
-    println!(
-        "{0: <36} | {1: <16} | {2: <8} | {3: <16}",
-        id, name, task_type, frequency
-    );
+/// Prints the output exactly as the task printed it
+pub fn print_report_output(report: &Report) {
+    out!("{}", report.output);
+}
+
+/// How an active task changed since the previous refresh of `tasks active --watch`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskChange {
+    Unchanged,
+    Started,
+    Finished,
 }
 
-pub fn pretty_print_tasks(tasks: Vec<Value>) {
-    format_and_print_task("Task ID", "Name", "Type", "Frequency");
-    println!("---------------------------------------------------------------------------------");
-    for task in tasks {
-        parse_and_print_task(task);
+const WATCH_HEADERS: [&str; 5] = ["Task ID", "Name", "Type", "State", "Running for"];
+
+/// Colors tasks that just started green and ones that just finished red
+fn style_watch_cell(header: &str, cell: &str) -> Option<Style> {
+    match (header, cell) {
+        ("State", "started") => Some(Style::Green),
+        ("State", "finished") => Some(Style::Red),
+        _ => style_cell(header, cell),
     }
 }
 
-pub fn parse_and_print_task(task: Value) {
-    // If id doesn't exist, then yeah, you can panic
-    let id = &task["id"].as_str().unwrap().to_string();
-    let task_name = match &task["task_name"] {
-        Value::String(v) => v.to_string(),
-        _ => {
-            print_invalid_task_value(id, "task_name", &task["task_name"]);
-            process::exit(1);
+pub fn print_watched_tasks(tasks: &[(Task, TaskChange)], now: chrono::NaiveDateTime) {
+    let rows = tasks
+        .iter()
+        .map(|(task, change)| {
+            let state = match change {
+                TaskChange::Unchanged => "running",
+                TaskChange::Started => "started",
+                TaskChange::Finished => "finished",
+            };
+            let running_for = task
+                .last_execution
+                .map(|started_at| format_duration(now - started_at))
+                .unwrap_or_default();
+            vec![
+                task.id.clone(),
+                task.task_name.clone(),
+                task.task_type.to_string(),
+                state.to_string(),
+                running_for,
+            ]
+        })
+        .collect::<Vec<_>>();
+    print_table(&WATCH_HEADERS, &rows, style_watch_cell);
+}
+
+const IMPORT_HEADERS: [&str; 4] = ["Name", "Old ID", "New ID", "Result"];
+const IMPORT_FIELDS: [&str; 5] = ["name", "old_id", "new_id", "result", "error"];
+
+/// Colors the tasks an operation succeeded for green and the ones it failed for red
+fn style_result_cell(header: &str, cell: &str) -> Option<Style> {
+    match header {
+        "Result" if cell.starts_with("failed") => Some(Style::Red),
+        "Result" if cell == "skipped" || cell == "unchanged" => Some(Style::Dim),
+        "Result" => Some(Style::Green),
+        "Old ID" | "New ID" => Some(Style::Dim),
+        _ => style_cell(header, cell),
+    }
+}
+
+fn result_cell(result: &str, error: &Option<String>) -> String {
+    match error {
+        Some(error) => format!("{}: {}", result, error),
+        None => result.to_string(),
+    }
+}
+
+pub fn print_import_results(results: &[ImportResult], options: &OutputOptions) -> Result<()> {
+    if options.quiet {
+        results
+            .iter()
+            .filter_map(|r| r.new_id.as_ref())
+            .for_each(|id| outln!("{}", id));
+        return Ok(());
+    }
+    if options.is_custom() {
+        return print_custom(results, &IMPORT_FIELDS, options);
+    }
+    match options.format.unwrap_or(OutputFormat::Table) {
+        format @ OutputFormat::Json | format @ OutputFormat::Yaml => print_serialized(&results, format)?,
+        format => {
+            let rows = results
+                .iter()
+                .map(|r| {
+                    vec![
+                        r.name.clone(),
+                        r.old_id.clone().unwrap_or_default(),
+                        r.new_id.clone().unwrap_or_default(),
+                        result_cell(r.result, &r.error),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            match format {
+                OutputFormat::Csv => print_delimited(&IMPORT_HEADERS, &rows, b',')?,
+                OutputFormat::Tsv => print_delimited(&IMPORT_HEADERS, &rows, b'\t')?,
+                _ => print_table(&IMPORT_HEADERS, &rows, style_result_cell),
+            }
         }
-    };
-    let task_type = match &task["task_type"] {
-        Value::String(v) => v.to_string(),
-        _ => {
-            print_invalid_task_value(id, "task_type", &task["task_type"]);
-            process::exit(1);
+    }
+    Ok(())
+}
+
+const BULK_HEADERS: [&str; 3] = ["Task ID", "Name", "Result"];
+const BULK_FIELDS: [&str; 4] = ["id", "name", "status", "error"];
+
+pub fn print_bulk_results(results: &[BulkResult], options: &OutputOptions) -> Result<()> {
+    if options.quiet {
+        results
+            .iter()
+            .filter(|r| r.error.is_none())
+            .for_each(|r| outln!("{}", r.id));
+        return Ok(());
+    }
+    if options.is_custom() {
+        return print_custom(results, &BULK_FIELDS, options);
+    }
+    match options.format.unwrap_or(OutputFormat::Table) {
+        format @ OutputFormat::Json | format @ OutputFormat::Yaml => print_serialized(&results, format)?,
+        format => {
+            let rows = results
+                .iter()
+                .map(|r| {
+                    let result = r.status.as_deref().unwrap_or("failed");
+                    vec![r.id.clone(), r.name.clone(), result_cell(result, &r.error)]
+                })
+                .collect::<Vec<_>>();
+            match format {
+                OutputFormat::Csv => print_delimited(&BULK_HEADERS, &rows, b',')?,
+                OutputFormat::Tsv => print_delimited(&BULK_HEADERS, &rows, b'\t')?,
+                _ => print_table(&BULK_HEADERS, &rows, style_result_cell),
+            }
         }
-    };
-    let frequency = match &task["frequency"] {
-        Value::String(v) => v.to_string(),
-        _ => {
-            print_invalid_task_value(id, "frequency", &task["frequency"]);
-            process::exit(1);
+    }
+    Ok(())
+}
+
+const TASK_HEADERS: [&str; 4] = ["Task ID", "Name", "Type", "Frequency"];
+const WIDE_TASK_HEADERS: [&str; 8] = [
+    "Task ID",
+    "Name",
+    "Type",
+    "Frequency",
+    "Props",
+    "Exec count",
+    "Last execution",
+    "Next execution",
+];
+const TASK_FIELDS: [&str; 9] = [
+    "id",
+    "task_name",
+    "task_type",
+    "frequency",
+    "task_props",
+    "exec_count",
+    "last_execution",
+    "next_execution",
+    "created_at",
+];
+/// Every field of a serialized task, optional ones are left out when they're empty
+const TASK_TEMPLATE_FIELDS: [&str; 10] = [
+    "id",
+    "task_name",
+    "task_type",
+    "frequency",
+    "task_props",
+    "exec_count",
+    "last_execution",
+    "next_execution",
+    "created_at",
+    "updated_at",
+];
+
+fn task_row(task: &Task) -> Vec<String> {
+    vec![
+        task.id.clone(),
+        task.task_name.clone(),
+        task.task_type.to_string(),
+        task.frequency.clone(),
+    ]
+}
+
+fn wide_task_row(task: &Task) -> Vec<String> {
+    vec![
+        task.id.clone(),
+        task.task_name.clone(),
+        task.task_type.to_string(),
+        task.frequency.clone(),
+        format_task_props(&task.task_props),
+        task.exec_count.map(|c| c.to_string()).unwrap_or_default(),
+        format_optional_date(task.last_execution),
+        format_optional_date(task.next_execution),
+    ]
+}
+
+fn full_task_row(task: &Task) -> Vec<String> {
+    vec![
+        task.id.clone(),
+        task.task_name.clone(),
+        task.task_type.to_string(),
+        task.frequency.clone(),
+        serde_json::to_string(&task.task_props).unwrap_or_default(),
+        task.exec_count.map(|c| c.to_string()).unwrap_or_default(),
+        format_optional_date(task.last_execution),
+        format_optional_date(task.next_execution),
+        format_optional_date(task.created_at),
+    ]
+}
+
+pub fn print_tasks(tasks: &[Task], options: &OutputOptions) -> Result<()> {
+    if options.quiet {
+        tasks.iter().for_each(|t| outln!("{}", t.id));
+        return Ok(());
+    }
+    if options.is_custom() {
+        return print_custom(tasks, &TASK_TEMPLATE_FIELDS, options);
+    }
+    match options.format.unwrap_or(OutputFormat::Table) {
+        OutputFormat::Table => {
+            let rows = tasks.iter().map(task_row).collect::<Vec<_>>();
+            print_table(&TASK_HEADERS, &rows, style_cell);
         }
-    };
-    let task_name = truncate_string_elliptic(task_name, 16);
-    format_and_print_task(id, &task_name, &task_type, &frequency);
+        OutputFormat::Wide => {
+            let rows = tasks.iter().map(wide_task_row).collect::<Vec<_>>();
+            print_table(&WIDE_TASK_HEADERS, &rows, style_cell);
+        }
+        OutputFormat::Csv => {
+            let rows = tasks.iter().map(full_task_row).collect::<Vec<_>>();
+            print_delimited(&TASK_FIELDS, &rows, b',')?;
+        }
+        OutputFormat::Tsv => {
+            let rows = tasks.iter().map(full_task_row).collect::<Vec<_>>();
+            print_delimited(&TASK_FIELDS, &rows, b'\t')?;
+        }
+        format => print_serialized(&tasks, format)?,
+    }
+    Ok(())
 }
 
-pub fn print_invalid_task_value(id: &str, key: &str, value: &Value) {
-    log::error!(
-        "Task with id: {} has an invalid property named: \"{}\" with value: \"{}\"",
-        id,
-        key,
-        value
-    );
-    log::error!("Please open an issue at https://github.com/gokayokyay/stewardx-cli and describe how to reproduce it, thanks!");
+/// Single task views print JSON unless told otherwise
+pub fn print_task(task: &Task, options: &OutputOptions) -> Result<()> {
+    if options.quiet {
+        outln!("{}", task.id);
+        return Ok(());
+    }
+    if options.is_custom() {
+        return print_custom(std::slice::from_ref(task), &TASK_TEMPLATE_FIELDS, options);
+    }
+    match options.format.unwrap_or(OutputFormat::Json) {
+        format @ OutputFormat::Json | format @ OutputFormat::Yaml => print_serialized(task, format),
+        format => print_tasks(std::slice::from_ref(task), &options.with_format(format)),
+    }
+}
+
+/// A task that was just created or changed prints as a table unless told otherwise, but as a
+/// single object like the other single task views when JSON or YAML is asked for
+pub fn print_changed_task(task: &Task, options: &OutputOptions) -> Result<()> {
+    print_task(task, &options.with_format(options.format.unwrap_or(OutputFormat::Table)))
 }
@@ -2,6 +2,7 @@ use std::{os::unix::prelude::PermissionsExt, process};
 
 use isahc::{ReadResponseExt, Request, RequestExt, config::{Configurable, Dialer, RedirectPolicy}};
 use serde_json::{Result as SerdeResult, Value};
+use stewardx_cli::Error;
 
 use crate::{output::{print_connection_failure, print_json_failure}, utils::{create_stewardx_dirs, get_binary_dir, get_nodejs_compatible_arch, get_socket_path}};
 
@@ -19,8 +20,8 @@ pub fn fetch_latest_binary() {
             let resp = match resp {
                 Ok(r) => r,
                 Err(e) => {
-                    print_json_failure(e);
-                    process::exit(1);
+                    print_json_failure(&e);
+                    process::exit(Error::from(e).exit_code());
                 }
             };
             let assets = match &resp["assets"].as_array() {
@@ -57,16 +58,16 @@ pub fn fetch_latest_binary() {
                         };
                     }
                     Err(e) => {
-                        print_connection_failure(e);
-                        process::exit(1)
+                        print_connection_failure(&e);
+                        process::exit(Error::from(e).exit_code());
                     }
                 }
                 break;
             }
         }
         Err(e) => {
-            print_connection_failure(e);
-            process::exit(1);
+            print_connection_failure(&e);
+            process::exit(Error::from(e).exit_code());
         }
     };
 }
@@ -95,24 +96,20 @@ pub fn start_stewardx() {
     if let Ok(Fork::Child) = daemon(false, false) {
         Command::new(binary_path)
             .output()
-            .and_then(|a| {
+            .inspect(|_| {
                 println!("Started StewardX!");
-                Ok(a)
             })
             .expect("failed to execute process");
         println!("Started StewardX!");
     } else {
         println!("Failed to start StewardX if you haven't already, please install it by running stewardx-cli install");
+        process::exit(1);
     }
 }
 
 pub fn check_if_stewardx_is_running() -> bool {
     let socket_path = get_socket_path();
-    if std::path::Path::exists(&socket_path) {
-        return true
-    } else {
-        return false
-    }
+    std::path::Path::exists(&socket_path)
 }
 
 pub fn stop_stewardx() {
@@ -126,11 +123,13 @@ pub fn stop_stewardx() {
                 println!("Successfully stopped StewardX.");
             } else {
                 println!("StewardX returned other than a goodbye message, here it is: {}", response);
+                process::exit(1);
             }
         }
         Err(e) => {
-            println!("{}", e.to_string());
-            print_connection_failure(e);
+            println!("{}", e);
+            print_connection_failure(&e);
+            process::exit(Error::from(e).exit_code());
         }
     };
 }
\ No newline at end of file
@@ -0,0 +1,167 @@
+use std::io::IsTerminal;
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    color::{paint, Stream, Style},
+    utils::truncate_string_elliptic,
+};
+
+const SEPARATOR: &str = " | ";
+/// Columns are never shrunk below this, unless their header is already narrower
+const MIN_COLUMN_WIDTH: usize = 8;
+
+/// Width of the terminal stdout is attached to, COLUMNS takes precedence if it's set
+fn terminal_width() -> Option<usize> {
+    if let Some(columns) = std::env::var("COLUMNS").ok().and_then(|c| c.parse().ok()) {
+        return Some(columns);
+    }
+    terminal_size::terminal_size().map(|(terminal_size::Width(w), _)| w as usize)
+}
+
+fn natural_widths(headers: &[&str], rows: &[Vec<String>]) -> Vec<usize> {
+    headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            rows.iter()
+                .map(|row| row[i].width())
+                .chain(std::iter::once(header.width()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+/// Ids are copied into other commands, so a cut one is useless
+fn is_id_column(header: &str) -> bool {
+    header == "id" || header.ends_with(" ID") || header.ends_with("_id")
+}
+
+/// Shrinks the widest columns one by one until the table fits into max_width, id columns are
+/// always kept whole
+fn shrink_widths(mut widths: Vec<usize>, headers: &[&str], max_width: usize) -> Vec<usize> {
+    let separators = SEPARATOR.len() * widths.len().saturating_sub(1);
+    let min_widths = headers
+        .iter()
+        .zip(&widths)
+        .map(|(h, w)| if is_id_column(h) { *w } else { h.width().min(MIN_COLUMN_WIDTH) })
+        .collect::<Vec<usize>>();
+    while widths.iter().sum::<usize>() + separators > max_width {
+        let widest = widths
+            .iter()
+            .enumerate()
+            .filter(|(i, w)| **w > min_widths[*i])
+            .max_by_key(|(_, w)| **w)
+            .map(|(i, _)| i);
+        match widest {
+            Some(i) => widths[i] -= 1,
+            None => break,
+        }
+    }
+    widths
+}
+
+/// Picks the style of a cell from its column's header and its contents
+pub type CellStyle = fn(&str, &str) -> Option<Style>;
+
+fn styled(cell: &str, style: Option<Style>) -> String {
+    match style {
+        Some(style) => paint(cell, style, Stream::Stdout),
+        None => cell.to_string(),
+    }
+}
+
+fn pad(cell: &str, width: usize, style: Option<Style>) -> String {
+    let cell = truncate_string_elliptic(cell.to_string(), width);
+    let padding = width.saturating_sub(cell.width());
+    format!("{}{}", styled(&cell, style), " ".repeat(padding))
+}
+
+/// A table whose column widths are fixed up front, so rows can be printed as they come
+pub struct Table {
+    headers: Vec<String>,
+    widths: Vec<usize>,
+    is_terminal: bool,
+    cell_style: CellStyle,
+}
+
+impl Table {
+    /// Sizes the columns to fit the given rows.
+    ///
+    /// On a terminal the columns are shrunk to fit its width. When stdout is redirected the
+    /// output is plain: cells aren't padded or truncated and the separator line is left out.
+    pub fn new(headers: &[&str], rows: &[Vec<String>], cell_style: CellStyle) -> Self {
+        let is_terminal = std::io::stdout().is_terminal();
+        let widths = natural_widths(headers, rows);
+        let widths = match terminal_width() {
+            Some(max_width) if is_terminal => shrink_widths(widths, headers, max_width),
+            _ => widths,
+        };
+        Table {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            widths,
+            is_terminal,
+            cell_style,
+        }
+    }
+
+    fn format_row(&self, row: &[String], cell_style: &dyn Fn(&str, &str) -> Option<Style>) -> String {
+        row.iter()
+            .zip(&self.widths)
+            .zip(&self.headers)
+            .map(|((cell, width), header)| {
+                let style = cell_style(header, cell);
+                if self.is_terminal {
+                    pad(cell, *width, style)
+                } else {
+                    styled(cell, style)
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(SEPARATOR)
+            .trim_end()
+            .to_string()
+    }
+
+    pub fn print_header(&self) {
+        outln!("{}", self.format_row(&self.headers, &|_, _| Some(Style::Bold)));
+        if self.is_terminal {
+            let separators = SEPARATOR.len() * self.widths.len().saturating_sub(1);
+            let line_length = self.widths.iter().sum::<usize>() + separators;
+            outln!("{}", paint(&"-".repeat(line_length), Style::Dim, Stream::Stdout));
+        }
+    }
+
+    pub fn print_row(&self, row: &[String]) {
+        outln!("{}", self.format_row(row, &self.cell_style));
+    }
+}
+
+/// Prints the rows aligned under the headers
+pub fn print_table(headers: &[&str], rows: &[Vec<String>], cell_style: CellStyle) {
+    let table = Table::new(headers, rows, cell_style);
+    table.print_header();
+    for row in rows {
+        table.print_row(row);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::shrink_widths;
+
+    #[test]
+    fn keeps_id_columns_whole() {
+        let headers = ["Task ID", "Name", "Frequency"];
+        let widths = shrink_widths(vec![36, 30, 20], &headers, 80);
+        assert_eq!(widths[0], 36);
+        assert!(widths.iter().sum::<usize>() + 6 <= 80);
+    }
+
+    #[test]
+    fn stops_at_the_minimum_width() {
+        let widths = shrink_widths(vec![36, 30], &["id", "Frequency"], 20);
+        assert_eq!(widths, vec![36, 8]);
+    }
+}
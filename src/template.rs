@@ -0,0 +1,132 @@
+use serde_json::Value;
+use stewardx_cli::{Error, Result};
+
+/// Short names accepted in place of the full field names
+fn resolve_alias(path: &str) -> &str {
+    match path {
+        "name" => "task_name",
+        "type" => "task_type",
+        "props" => "task_props",
+        "executed_at" => "created_at",
+        _ => path,
+    }
+}
+
+/// Follows a dotted path like task_props.image.c, numbers index into arrays
+pub fn lookup_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut parts = path.split('.');
+    let first = resolve_alias(parts.next()?);
+    let mut current = value.get(first)?;
+    for part in parts {
+        current = match current {
+            Value::Array(items) => items.get(part.parse::<usize>().ok()?)?,
+            _ => current.get(part)?,
+        };
+    }
+    Some(current)
+}
+
+pub fn render_value(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(v) => v.to_string(),
+    }
+}
+
+/// Byte ranges of the {{path}} placeholders in the template, an unterminated one is left as text
+fn placeholders(template: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = template[offset..].find("{{") {
+        let start = offset + start;
+        let end = match template[start..].find("}}") {
+            Some(end) => start + end + 2,
+            None => break,
+        };
+        ranges.push((start, end));
+        offset = end;
+    }
+    ranges
+}
+
+fn placeholder_path(template: &str, (start, end): (usize, usize)) -> &str {
+    template[start + 2..end - 2].trim()
+}
+
+/// Checks that every path in the template starts with one of the fields, so a misspelled field
+/// is reported instead of rendered as a blank
+pub fn check_template(template: &str, fields: &[&str]) -> Result<()> {
+    for range in placeholders(template) {
+        let path = placeholder_path(template, range);
+        let field = path.split('.').next().unwrap_or_default();
+        if !fields.contains(&resolve_alias(field)) {
+            return Err(Error::InvalidArgument {
+                name: "template",
+                message: format!("unknown field \"{}\", expected one of {}", field, fields.join(", ")),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Replaces every {{path}} in the template with the value found at that path
+pub fn render_template(template: &str, value: &Value) -> String {
+    let mut rendered = String::new();
+    let mut rest = 0;
+    for range in placeholders(template) {
+        rendered.push_str(&template[rest..range.0]);
+        rendered.push_str(&render_value(lookup_path(value, placeholder_path(template, range))));
+        rest = range.1;
+    }
+    rendered.push_str(&template[rest..]);
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_template, lookup_path, render_template};
+    use serde_json::json;
+
+    fn task() -> serde_json::Value {
+        json!({
+            "id": "517e2f5a",
+            "task_name": "backup-db",
+            "task_type": "DockerTask",
+            "task_props": {"image": {"t": "Image", "c": "alpine"}, "env": ["A=1", "B=2"]},
+            "exec_count": 3,
+        })
+    }
+
+    #[test]
+    fn follows_dotted_paths_and_indexes() {
+        let task = task();
+        assert_eq!(lookup_path(&task, "task_props.image.c"), Some(&json!("alpine")));
+        assert_eq!(lookup_path(&task, "props.env.1"), Some(&json!("B=2")));
+        assert_eq!(lookup_path(&task, "props.env.2"), None);
+        assert_eq!(lookup_path(&task, "props.env.x"), None);
+        assert_eq!(lookup_path(&task, "name.first"), None);
+    }
+
+    #[test]
+    fn renders_aliases_and_missing_values() {
+        let task = task();
+        let rendered = render_template("{{ name }} ({{type}}) ran {{exec_count}}x{{last_execution}}", &task);
+        assert_eq!(rendered, "backup-db (DockerTask) ran 3x");
+        assert_eq!(render_template("{{props.image}}", &task), r#"{"c":"alpine","t":"Image"}"#);
+    }
+
+    #[test]
+    fn leaves_unterminated_placeholders_as_text() {
+        assert_eq!(render_template("{{id}} {{name", &task()), "517e2f5a {{name");
+        assert_eq!(render_template("}} {{", &task()), "}} {{");
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        let fields = ["id", "task_name", "task_props", "last_execution"];
+        assert!(check_template("{{id}} {{name}} {{props.image.c}} {{last_execution}}", &fields).is_ok());
+        assert!(check_template("{{task_nmae}}", &fields).is_err());
+        assert!(check_template("{{id}} {{task_nmae", &fields).is_ok());
+    }
+}
@@ -1,35 +1,94 @@
-use std::{path::PathBuf, process, str::FromStr};
-
-pub fn parse_cron_frequency(frequency: &str) -> String {
-    let cron_str = frequency
-        .starts_with("Every(")
-        .then(|| remove_cron_freq_prefix(frequency))
-        .or_else(|| Some(frequency));
-    let cron_str = match cron_str {
-        Some(c) => c,
-        None => {
-            eprintln!("Please enter a valid cron string.");
-            process::exit(1);
-        }
+use std::{path::PathBuf, str::FromStr, time::Duration};
+
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+
+use stewardx_cli::{DockerImage, DockerImageType, Error, NewTask, Result, TaskProps, TaskType};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Accepts either Hook or a cron string, with or without the Every(...) wrapper
+pub fn parse_frequency(frequency: &str) -> Result<String> {
+    if frequency == "Hook" {
+        Ok(frequency.to_string())
+    } else {
+        parse_cron_frequency(frequency)
+    }
+}
+
+pub fn parse_cron_frequency(frequency: &str) -> Result<String> {
+    let cron_str = if frequency.starts_with("Every(") {
+        remove_cron_freq_prefix(frequency)
+    } else {
+        frequency
+    };
+    if cron_str.split(' ').count() != 6 {
+        return Err(Error::InvalidCron(
+            "StewardX's cron frequency needs to take 6 crontime inputs, like * * * * * *".into(),
+        ));
+    }
+    if let Err(e) = cron::Schedule::from_str(cron_str) {
+        return Err(Error::InvalidCron(e.to_string()));
+    }
+    Ok(format!("Every({})", cron_str))
+}
+
+/// Checks a task written by hand, like in a YAML document, and normalizes its frequency
+pub fn validate_task(mut task: NewTask) -> Result<NewTask> {
+    let invalid = |field, message: &str| {
+        Err(Error::InvalidTaskField {
+            field,
+            message: message.to_string(),
+        })
     };
-    cron_str.split(" ").collect::<Vec<&str>>().len().ne(&6).then(||{
-        eprintln!("Please enter a valid cron string. Hint: StewardX's cron frequency needs to take 6 crontime inputs, like * * * * * *");
-        process::exit(1)
-    });
-    match cron::Schedule::from_str(cron_str) {
-        Ok(_a) => {}
-        Err(_e) => {
-            eprintln!("Please enter a valid cron string.");
-            process::exit(1);
+    if task.task_name.trim().is_empty() {
+        return invalid("task_name", "task name can't be empty");
+    }
+    task.frequency = parse_frequency(&task.frequency)?;
+    match (task.task_type, &task.task_props) {
+        (TaskType::CmdTask, TaskProps::Cmd(cmd)) if cmd.command.trim().is_empty() => {
+            invalid("command", "command can't be empty")
+        }
+        (TaskType::DockerTask, TaskProps::Docker(docker)) if docker.image.c.trim().is_empty() => {
+            invalid("image", "image name or Dockerfile contents can't be empty")
         }
+        (TaskType::CmdTask, TaskProps::Cmd(_)) | (TaskType::DockerTask, TaskProps::Docker(_)) => Ok(task),
+        (TaskType::CmdTask, _) => invalid("task_props", "a CmdTask needs a command"),
+        (TaskType::DockerTask, _) => invalid(
+            "task_props",
+            "a DockerTask needs an image, with its type t (File or Image) and contents c",
+        ),
+    }
+}
+
+/// Parses the docker type given on the command line, either file or image
+pub fn parse_docker_type(docker_type: &str) -> Result<DockerImageType> {
+    match docker_type {
+        "file" => Ok(DockerImageType::File),
+        "image" => Ok(DockerImageType::Image),
+        _ => Err(Error::InvalidTaskField {
+            field: "type",
+            message: "please supply either \"file\" or \"image\"".into(),
+        }),
+    }
+}
+
+/// Builds the image from an image name, or from a Dockerfile by reading it from the given path
+pub fn docker_image(docker_type: DockerImageType, contents: &str) -> Result<DockerImage> {
+    let contents = match docker_type {
+        DockerImageType::File => std::fs::read_to_string(contents).map_err(|e| Error::InvalidTaskField {
+            field: "contents",
+            message: format!("couldn't read the Dockerfile, please make sure its path is correct ({})", e),
+        })?,
+        DockerImageType::Image => contents.to_string(),
     };
-    let parsed_frequency = format!("Every({})", cron_str);
-    return parsed_frequency;
+    Ok(DockerImage {
+        t: docker_type,
+        c: contents,
+    })
 }
 
 pub fn remove_cron_freq_prefix(frequency: &str) -> &str {
     let mut chars = frequency.chars();
-    while let Some(a) = chars.next() {
+    for a in chars.by_ref() {
         if a.to_string() == "(" {
             break;
         }
@@ -38,36 +97,100 @@ pub fn remove_cron_freq_prefix(frequency: &str) -> &str {
     chars.as_str()
 }
 
+/// Truncates the string to fit into the given display width, marking the cut with an ellipsis
 pub fn truncate_string_elliptic(string: String, to: usize) -> String {
-    let mut cloned = string.clone();
-    if cloned.len().ge(&(to - 1)) {
-        cloned.truncate(to - 1);
-        format!("{}…", cloned)
-    } else {
-        cloned
+    if string.width() <= to {
+        return string;
     }
-}
-
-pub fn capitalize(s: &str) -> String {
-    let mut c = s.chars();
-    match c.next() {
-        None => String::new(),
-        Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
+    let mut truncated = String::new();
+    let mut width = 0;
+    for c in string.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if width + char_width + 1 > to {
+            break;
+        }
+        width += char_width;
+        truncated.push(c);
+    }
+    if to > 0 {
+        truncated.push('…');
     }
+    truncated
 }
 
 pub fn format_date(date: chrono::NaiveDateTime) -> String {
     date.format("%Y-%m-%d %H:%M:%S UTC").to_string()
 }
 
+/// Formats durations like 1h 2m 3s, or 850ms when it's shorter than a second
+pub fn format_duration(duration: chrono::Duration) -> String {
+    let millis = duration.num_milliseconds().max(0);
+    if millis < 1000 {
+        return format!("{}ms", millis);
+    }
+    let seconds = millis / 1000;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+    match (hours, minutes) {
+        (0, 0) => format!("{}s", seconds),
+        (0, _) => format!("{}m {}s", minutes, seconds),
+        _ => format!("{}h {}m {}s", hours, minutes, seconds),
+    }
+}
+
+/// Parses durations like 90s, 15m, 2h, 1d or 1w
+pub fn parse_duration(duration: &str) -> std::result::Result<Duration, String> {
+    let duration = duration.trim();
+    let unit_start = duration
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(duration.len());
+    let (amount, unit) = duration.split_at(unit_start);
+    let amount = amount
+        .parse::<u64>()
+        .map_err(|_| format!("\"{}\" isn't a duration like 30s, 15m, 2h or 1d", duration))?;
+    let seconds = match unit {
+        "ms" => return Ok(Duration::from_millis(amount)),
+        "s" | "" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(format!("unknown duration unit \"{}\", use one of ms, s, m, h, d or w", unit)),
+    };
+    amount
+        .checked_mul(seconds)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("\"{}\" is too long", duration))
+}
+
+/// Parses either a duration relative to now, like 2h, or a date like 2026-10-01 or 2026-10-01T12:00:00, in UTC
+pub fn parse_time(time: &str) -> std::result::Result<NaiveDateTime, String> {
+    // A bare number like 2026 is more likely a mistyped date than seconds, so a unit is required
+    let has_unit = time.trim().ends_with(|c: char| c.is_ascii_alphabetic());
+    if let Some(duration) = parse_duration(time).ok().filter(|_| has_unit) {
+        return chrono::Duration::from_std(duration)
+            .ok()
+            .and_then(|duration| Utc::now().naive_utc().checked_sub_signed(duration))
+            .ok_or_else(|| format!("\"{}\" is too long ago", time));
+    }
+    NaiveDateTime::from_str(time)
+        .or_else(|_| NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S"))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(time, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })
+        .ok_or_else(|| format!("\"{}\" is neither a duration like 2h nor a date like 2026-10-01", time))
+}
+
 pub fn get_nodejs_compatible_arch() -> &'static str {
     let arch = std::env::consts::ARCH;
-    return match arch {
+    match arch {
         "x86_64" => "x64",
         "x86" => "x32",
         "aarch64" => "arm64",
         _ => arch
-    };
+    }
 }
 
 pub fn get_binary_dir() -> PathBuf {
@@ -85,7 +208,7 @@ pub fn get_binary_dir() -> PathBuf {
                     s
                 },
                 None => {
-                    return PathBuf::from_str("$HOME/.stewardx").unwrap();
+                    PathBuf::from_str("$HOME/.stewardx").unwrap()
                 }
             }
         }
@@ -107,3 +230,52 @@ pub fn get_socket_path() -> PathBuf {
     socket_path.push("stewardx.sock");
     socket_path
 }
+
+#[cfg(test)]
+mod tests {
+    use unicode_width::UnicodeWidthStr;
+
+    use super::{parse_duration, parse_time, truncate_string_elliptic};
+
+    #[test]
+    fn truncates_turkish_names_on_char_boundaries() {
+        assert_eq!(truncate_string_elliptic("Türkçe görev".to_string(), 6), "Türkç…");
+        assert_eq!(truncate_string_elliptic("ığüşöç".to_string(), 3), "ığ…");
+    }
+
+    #[test]
+    fn counts_emoji_as_two_columns() {
+        let truncated = truncate_string_elliptic("🚀🚀🚀 launch".to_string(), 6);
+        assert_eq!(truncated, "🚀🚀…");
+        assert!(truncated.width() <= 6);
+        // Doesn't split the emoji when only one column is left for it
+        assert_eq!(truncate_string_elliptic("a🚀b".to_string(), 3), "a…");
+    }
+
+    #[test]
+    fn zero_width_is_empty() {
+        assert_eq!(truncate_string_elliptic("Türkçe".to_string(), 0), "");
+        assert_eq!(truncate_string_elliptic(String::new(), 0), "");
+    }
+
+    #[test]
+    fn exact_fit_is_left_alone() {
+        assert_eq!(truncate_string_elliptic("görev".to_string(), 5), "görev");
+        assert_eq!(truncate_string_elliptic("🚀🚀".to_string(), 4), "🚀🚀");
+        assert_eq!(truncate_string_elliptic("görev".to_string(), 4), "gör…");
+    }
+
+    #[test]
+    fn time_needs_a_unit_or_a_date() {
+        assert!(parse_time("2026").is_err());
+        assert!(parse_time("2h").is_ok());
+        assert_eq!(parse_time("2026-10-01").unwrap().to_string(), "2026-10-01 00:00:00");
+    }
+
+    #[test]
+    fn huge_durations_are_rejected() {
+        assert!(parse_duration("18446744073709551615w").is_err());
+        assert!(parse_time("99999999999w").is_err());
+        assert_eq!(parse_duration("90").unwrap().as_secs(), 90);
+    }
+}